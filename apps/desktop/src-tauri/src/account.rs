@@ -1,12 +1,30 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
-use tauri::Manager;
-
-const ACCOUNT_FILE: &str = "account_settings.json";
-const PREFERENCES_FILE: &str = "user_preferences.json";
-const SESSION_FILE: &str = "auth_session.json";
-const PROFILE_IMAGE_DIR: &str = "profile_images";
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{Emitter, State};
+
+use crate::alarm::{self, AlarmManagerState};
+use crate::api::{ApiClient, ApiError};
+use crate::backup;
+use crate::config_store::{self, config_path};
+use crate::entitlements;
+use crate::error::{AppError, AppResult};
+use crate::migrations;
+use crate::nickname::{self, NicknameError};
+use crate::team_cache;
+use crate::time::now_unix;
+use crate::workspace;
+use crate::workspace_session;
+use crate::workspace_storage;
+use crate::workspace_template;
+
+const ACCOUNT_FILE: &str = migrations::ACCOUNT_SETTINGS.file;
+const SESSION_FILE: &str = migrations::AUTH_SESSION.file;
+pub const PROFILE_IMAGE_DIR: &str = "profile_images";
+const NICKNAME_TAKEN_CACHE_TTL_SECS: i64 = 10 * 60;
+const SESSION_EXPIRING_SOON_SECS: i64 = 5 * 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountSettings {
@@ -18,18 +36,6 @@ pub struct AccountSettings {
     pub reserved_nicknames: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserPreferences {
-    #[serde(default = "default_theme")]
-    pub theme: String,
-    #[serde(default = "default_language")]
-    pub language: String,
-    #[serde(default = "default_timezone")]
-    pub timezone: String,
-    #[serde(default = "default_notifications_enabled")]
-    pub notifications_enabled: bool,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthSession {
     #[serde(default)]
@@ -48,24 +54,121 @@ pub struct AuthSession {
     pub expires_at_unix: Option<i64>,
 }
 
-fn default_nickname() -> String {
-    "DeskCal User".to_string()
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Valid,
+    ExpiringSoon,
+    ExpiredRefreshable,
+    Invalid,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionInvalidReason {
+    NoSession,
+    MissingAccessToken,
+    ExpiredWithoutRefreshToken,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionState {
+    pub status: SessionStatus,
+    pub session: Option<AuthSession>,
+    /// Seconds until `expires_at_unix`; negative once expired.
+    pub expires_in_secs: Option<i64>,
+    pub reason: Option<SessionInvalidReason>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInvalidatedPayload {
+    pub reason: SessionInvalidReason,
+    pub member_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogoutOptions {
+    /// Leave workspaces, alarms and account settings on disk; only the
+    /// session is removed.
+    #[serde(default)]
+    pub keep_local_data: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LogoutReport {
+    pub kept_local_data: bool,
+    pub cleared: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl LogoutReport {
+    fn record(&mut self, artifact: &str, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.cleared.push(artifact.to_string()),
+            Err(e) => {
+                log::error!("logout: failed to clear {}: {}", artifact, e);
+                self.failed.push(artifact.to_string());
+            }
+        }
+    }
 }
 
-fn default_theme() -> String {
-    "system".to_string()
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AvailabilitySource {
+    Server,
+    Cache,
+    Local,
 }
 
-fn default_language() -> String {
-    "ko".to_string()
+#[derive(Debug, Clone, Serialize)]
+pub struct NicknameAvailability {
+    pub nickname: String,
+    pub available: bool,
+    pub source: AvailabilitySource,
+    /// `true` when the server could not be reached and only the local
+    /// reserved list was consulted.
+    pub best_effort: bool,
+    pub conflicts_with: Option<String>,
+}
+
+/// Nicknames the server reported as taken, keyed by skeleton. Only negative
+/// answers are cached: a free nickname can be claimed by someone else.
+#[derive(Default)]
+pub struct NicknameAvailabilityCache {
+    taken: Mutex<HashMap<String, i64>>,
+}
+
+impl NicknameAvailabilityCache {
+    fn is_known_taken(&self, skeleton: &str, now: i64) -> bool {
+        let mut taken = match self.taken.lock() {
+            Ok(guard) => guard,
+            Err(_) => return false,
+        };
+        taken.retain(|_, checked_at| now - *checked_at < NICKNAME_TAKEN_CACHE_TTL_SECS);
+        taken.contains_key(skeleton)
+    }
+
+    fn remember_taken(&self, skeleton: String, now: i64) {
+        if let Ok(mut taken) = self.taken.lock() {
+            taken.insert(skeleton, now);
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut taken) = self.taken.lock() {
+            taken.clear();
+        }
+    }
 }
 
-fn default_timezone() -> String {
-    "Asia/Seoul".to_string()
+#[derive(Debug, Deserialize)]
+struct NicknameCheckResponse {
+    available: bool,
 }
 
-fn default_notifications_enabled() -> bool {
-    true
+fn default_nickname() -> String {
+    "DeskCal User".to_string()
 }
 
 fn default_account_settings() -> AccountSettings {
@@ -76,15 +179,6 @@ fn default_account_settings() -> AccountSettings {
     }
 }
 
-fn default_user_preferences() -> UserPreferences {
-    UserPreferences {
-        theme: default_theme(),
-        language: default_language(),
-        timezone: default_timezone(),
-        notifications_enabled: default_notifications_enabled(),
-    }
-}
-
 fn default_auth_session() -> AuthSession {
     AuthSession {
         provider: None,
@@ -97,85 +191,140 @@ fn default_auth_session() -> AuthSession {
     }
 }
 
-fn config_path(app: &tauri::AppHandle, filename: &str) -> Result<PathBuf, String> {
-    app.path()
-        .app_config_dir()
-        .map(|dir| dir.join(filename))
-        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
-}
-
-fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
-    let raw = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&raw).ok()
-}
-
-fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
-
-    let json = serde_json::to_string_pretty(value)
-        .map_err(|e| format!("Failed to serialize json: {}", e))?;
-    fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))
-}
-
-fn normalize_nickname(nickname: &str) -> String {
-    nickname.trim().to_lowercase()
-}
-
-fn validate_nickname(nickname: &str) -> Result<String, String> {
-    let trimmed = nickname.trim();
-    let len = trimmed.chars().count();
-    if len < 2 || len > 20 {
-        return Err("Nickname must be between 2 and 20 characters".to_string());
-    }
-    Ok(trimmed.to_string())
-}
-
-fn load_account_settings(app: &tauri::AppHandle) -> AccountSettings {
+pub fn load_account_settings(app: &tauri::AppHandle) -> AccountSettings {
     let path = match config_path(app, ACCOUNT_FILE) {
         Ok(path) => path,
         Err(_) => return default_account_settings(),
     };
-    read_json::<AccountSettings>(&path).unwrap_or_else(default_account_settings)
+    config_store::read::<AccountSettings>(&path, &migrations::ACCOUNT_SETTINGS).unwrap_or_else(default_account_settings)
 }
 
 fn save_account_settings(app: &tauri::AppHandle, settings: &AccountSettings) -> Result<(), String> {
     let path = config_path(app, ACCOUNT_FILE)?;
-    write_json(&path, settings)
-}
-
-fn load_preferences(app: &tauri::AppHandle) -> UserPreferences {
-    let path = match config_path(app, PREFERENCES_FILE) {
-        Ok(path) => path,
-        Err(_) => return default_user_preferences(),
-    };
-    read_json::<UserPreferences>(&path).unwrap_or_else(default_user_preferences)
+    config_store::write(&path, &migrations::ACCOUNT_SETTINGS, settings)
 }
 
-fn save_preferences(app: &tauri::AppHandle, preferences: &UserPreferences) -> Result<(), String> {
-    let path = config_path(app, PREFERENCES_FILE)?;
-    write_json(&path, preferences)
+/// Load, modify and save `account_settings.json` under the config file lock.
+pub fn update_account_settings<R>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&mut AccountSettings) -> AppResult<R>,
+) -> AppResult<R> {
+    let path = config_path(app, ACCOUNT_FILE).map_err(AppError::storage)?;
+    config_store::with_lock(&path, || {
+        let mut settings = load_account_settings(app);
+        let result = f(&mut settings)?;
+        save_account_settings(app, &settings).map_err(AppError::storage)?;
+        Ok(result)
+    })?
 }
 
-fn load_session(app: &tauri::AppHandle) -> AuthSession {
+pub fn load_session(app: &tauri::AppHandle) -> AuthSession {
     let path = match config_path(app, SESSION_FILE) {
         Ok(path) => path,
         Err(_) => return default_auth_session(),
     };
-    read_json::<AuthSession>(&path).unwrap_or_else(default_auth_session)
+    config_store::read::<AuthSession>(&path, &migrations::AUTH_SESSION).unwrap_or_else(default_auth_session)
 }
 
 fn save_session(app: &tauri::AppHandle, session: &AuthSession) -> Result<(), String> {
-    let path = config_path(app, SESSION_FILE)?;
-    write_json(&path, session)
+    config_store::write_locked(app, &migrations::AUTH_SESSION, session)
+}
+
+fn has_value(value: &Option<String>) -> bool {
+    value.as_deref().map(|v| !v.trim().is_empty()).unwrap_or(false)
+}
+
+fn evaluate_session(
+    session: &AuthSession,
+    now: i64,
+) -> (SessionStatus, Option<SessionInvalidReason>) {
+    let has_access = has_value(&session.access_token);
+    let has_refresh = has_value(&session.refresh_token);
+
+    if !has_access && !has_refresh {
+        let reason = if session.member_id.is_none() && session.provider.is_none() {
+            SessionInvalidReason::NoSession
+        } else {
+            SessionInvalidReason::MissingAccessToken
+        };
+        return (SessionStatus::Invalid, Some(reason));
+    }
+
+    let expired = !has_access
+        || session
+            .expires_at_unix
+            .map(|expires_at| expires_at <= now)
+            .unwrap_or(false);
+    if expired {
+        if has_refresh {
+            return (SessionStatus::ExpiredRefreshable, None);
+        }
+        return (
+            SessionStatus::Invalid,
+            Some(SessionInvalidReason::ExpiredWithoutRefreshToken),
+        );
+    }
+
+    match session.expires_at_unix {
+        Some(expires_at) if expires_at - now <= SESSION_EXPIRING_SOON_SECS => {
+            (SessionStatus::ExpiringSoon, None)
+        }
+        _ => (SessionStatus::Valid, None),
+    }
+}
+
+/// Typed view of the stored session. Sessions that can no longer be used are
+/// removed from disk and a `session://invalidated` event is emitted.
+pub fn session_state(app: &tauri::AppHandle) -> SessionState {
+    let session = load_session(app);
+    let now = now_unix();
+    let (status, reason) = evaluate_session(&session, now);
+    let expires_in_secs = session.expires_at_unix.map(|expires_at| expires_at - now);
+
+    if status != SessionStatus::Invalid {
+        return SessionState {
+            status,
+            session: Some(session),
+            expires_in_secs,
+            reason,
+        };
+    }
+
+    if reason != Some(SessionInvalidReason::NoSession) {
+        if let Err(e) = clear_session(app) {
+            log::error!("session: failed to clear unusable session: {}", e);
+        }
+        if let Some(reason) = reason {
+            let payload = SessionInvalidatedPayload {
+                reason,
+                member_id: session.member_id.clone(),
+            };
+            if let Err(e) = app.emit("session://invalidated", payload) {
+                log::warn!("session: failed to emit invalidation: {}", e);
+            }
+        }
+    }
+
+    SessionState {
+        status,
+        session: None,
+        expires_in_secs,
+        reason,
+    }
 }
 
 fn clear_session(app: &tauri::AppHandle) -> Result<(), String> {
     let path = config_path(app, SESSION_FILE)?;
-    if path.exists() {
-        fs::remove_file(path).map_err(|e| format!("Failed to remove session file: {}", e))?;
+    config_store::remove(&path)
+}
+
+/// Remove `account_settings.json` and every stored profile image.
+fn clear_local_account_data(app: &tauri::AppHandle) -> Result<(), String> {
+    config_store::remove(&config_path(app, ACCOUNT_FILE)?)?;
+    let profile_dir = config_path(app, PROFILE_IMAGE_DIR)?;
+    if profile_dir.exists() {
+        fs::remove_dir_all(profile_dir)
+            .map_err(|e| format!("Failed to remove profile images: {}", e))?;
     }
     Ok(())
 }
@@ -189,57 +338,116 @@ pub fn get_account_settings(app: tauri::AppHandle) -> AccountSettings {
 pub fn set_reserved_nicknames(
     app: tauri::AppHandle,
     reserved_nicknames: Vec<String>,
-) -> Result<(), String> {
-    let mut settings = load_account_settings(&app);
-    settings.reserved_nicknames = reserved_nicknames;
-    save_account_settings(&app, &settings)
+) -> AppResult<()> {
+    update_account_settings(&app, |settings| {
+        settings.reserved_nicknames = reserved_nicknames;
+        Ok(())
+    })
+}
+
+async fn check_nickname_on_server(client: &ApiClient, nickname: &str) -> Result<bool, ApiError> {
+    let path = format!(
+        "/api/me/account/nickname-check?nickname={}",
+        urlencoding::encode(nickname)
+    );
+    let response: NicknameCheckResponse = client.get(&path).await?;
+    Ok(response.available)
 }
 
 #[tauri::command]
-pub fn is_nickname_available(app: tauri::AppHandle, nickname: String) -> Result<bool, String> {
-    let nickname = validate_nickname(&nickname)?;
+pub async fn is_nickname_available(
+    app: tauri::AppHandle,
+    cache: State<'_, NicknameAvailabilityCache>,
+    nickname: String,
+) -> AppResult<NicknameAvailability> {
+    let nickname = nickname::validate_nickname(&nickname)?;
     let settings = load_account_settings(&app);
+    let availability = |available, source, best_effort, conflicts_with| NicknameAvailability {
+        nickname: nickname.clone(),
+        available,
+        source,
+        best_effort,
+        conflicts_with,
+    };
 
-    if normalize_nickname(&settings.nickname) == normalize_nickname(&nickname) {
-        return Ok(true);
+    if nickname::is_same_nickname(&settings.nickname, &nickname) {
+        return Ok(availability(true, AvailabilitySource::Local, false, None));
     }
 
-    let exists = settings
-        .reserved_nicknames
-        .iter()
-        .any(|used| normalize_nickname(used) == normalize_nickname(&nickname));
+    // Confusables of reserved names are rejected regardless of what the server says.
+    if let Some(used) = nickname::find_reserved_conflict(&nickname, &settings.reserved_nicknames) {
+        return Ok(availability(
+            false,
+            AvailabilitySource::Local,
+            false,
+            Some(used.clone()),
+        ));
+    }
+
+    let skeleton = nickname::nickname_skeleton(&nickname);
+    let now = now_unix();
+    if cache.is_known_taken(&skeleton, now) {
+        return Ok(availability(false, AvailabilitySource::Cache, false, None));
+    }
 
-    Ok(!exists)
+    let client = ApiClient::from_app(&app);
+    match check_nickname_on_server(&client, &nickname).await {
+        Ok(available) => {
+            if !available {
+                cache.remember_taken(skeleton, now);
+            }
+            Ok(availability(available, AvailabilitySource::Server, false, None))
+        }
+        // Only an unreachable server falls back to the local list; a rejected
+        // session or a server error is not an answer about the nickname.
+        Err(e @ ApiError::Offline(_)) => {
+            log::warn!("nickname: server check failed, using local list: {}", e);
+            Ok(availability(true, AvailabilitySource::Local, true, None))
+        }
+        Err(e) => Err(e.into()),
+    }
 }
 
 #[tauri::command]
-pub fn update_nickname(app: tauri::AppHandle, nickname: String) -> Result<AccountSettings, String> {
-    let nickname = validate_nickname(&nickname)?;
-    let mut settings = load_account_settings(&app);
-
-    let duplicated = settings
-        .reserved_nicknames
-        .iter()
-        .any(|used| normalize_nickname(used) == normalize_nickname(&nickname))
-        && normalize_nickname(&settings.nickname) != normalize_nickname(&nickname);
-
-    if duplicated {
-        return Err("Nickname is already in use".to_string());
-    }
+pub fn update_nickname(
+    app: tauri::AppHandle,
+    nickname: String,
+) -> AppResult<AccountSettings> {
+    let nickname = nickname::validate_nickname(&nickname)?;
+    update_account_settings(&app, |settings| {
+        if !nickname::is_same_nickname(&settings.nickname, &nickname) {
+            if let Some(used) =
+                nickname::find_reserved_conflict(&nickname, &settings.reserved_nicknames)
+            {
+                return Err(NicknameError::Reserved {
+                    conflicts_with: used.clone(),
+                }
+                .into());
+            }
+        }
 
-    settings.nickname = nickname;
-    save_account_settings(&app, &settings)?;
-    Ok(settings)
+        settings.nickname = nickname;
+        Ok(settings.clone())
+    })
 }
 
 #[tauri::command]
-pub fn upload_profile_image(app: tauri::AppHandle, source_path: String) -> Result<String, String> {
+pub fn upload_profile_image(app: tauri::AppHandle, source_path: String) -> AppResult<String> {
     let source = PathBuf::from(source_path.trim());
+    let details = serde_json::json!({ "path": source.to_string_lossy() });
     if !source.exists() {
-        return Err("Profile image source file does not exist".to_string());
+        return Err(AppError::not_found(
+            "profile_image_source_missing",
+            "Profile image source file does not exist",
+        )
+        .with_details(details));
     }
     if !source.is_file() {
-        return Err("Profile image source path is not a file".to_string());
+        return Err(AppError::validation(
+            "profile_image_source_not_file",
+            "Profile image source path is not a file",
+        )
+        .with_details(details));
     }
 
     let ext = source
@@ -248,80 +456,142 @@ pub fn upload_profile_image(app: tauri::AppHandle, source_path: String) -> Resul
         .unwrap_or("png")
         .to_lowercase();
 
-    let profile_dir = config_path(&app, PROFILE_IMAGE_DIR)?;
-    fs::create_dir_all(&profile_dir)
-        .map_err(|e| format!("Failed to create profile image directory: {}", e))?;
+    let profile_dir = config_path(&app, PROFILE_IMAGE_DIR).map_err(AppError::storage)?;
+    fs::create_dir_all(&profile_dir).map_err(|e| {
+        AppError::storage(format!("Failed to create profile image directory: {}", e))
+    })?;
 
     let filename = format!(
         "profile_{}.{}",
         std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|e| format!("Failed to read system time: {}", e))?
+            .map_err(|e| AppError::io("clock_error", format!("Failed to read system time: {}", e)))?
             .as_millis(),
         ext
     );
     let target = profile_dir.join(filename);
-    fs::copy(&source, &target).map_err(|e| format!("Failed to copy profile image: {}", e))?;
-
-    let mut settings = load_account_settings(&app);
-    if let Some(old_path) = settings.profile_image_path.as_deref() {
-        let old = PathBuf::from(old_path);
-        if old.exists() {
-            let _ = fs::remove_file(old);
-        }
-    }
+    fs::copy(&source, &target)
+        .map_err(|e| AppError::storage(format!("Failed to copy profile image: {}", e)))?;
 
     let target_str = target.to_string_lossy().to_string();
-    settings.profile_image_path = Some(target_str.clone());
-    save_account_settings(&app, &settings)?;
+    update_account_settings(&app, |settings| {
+        if let Some(old_path) = settings.profile_image_path.as_deref() {
+            let old = PathBuf::from(old_path);
+            if old.exists() {
+                let _ = fs::remove_file(old);
+            }
+        }
+        settings.profile_image_path = Some(target_str.clone());
+        Ok(())
+    })?;
 
     Ok(target_str)
 }
 
 #[tauri::command]
-pub fn delete_profile_image(app: tauri::AppHandle) -> Result<(), String> {
-    let mut settings = load_account_settings(&app);
-    if let Some(path) = settings.profile_image_path.as_deref() {
-        let profile = PathBuf::from(path);
-        if profile.exists() {
-            fs::remove_file(profile)
-                .map_err(|e| format!("Failed to delete profile image: {}", e))?;
+pub fn delete_profile_image(app: tauri::AppHandle) -> AppResult<()> {
+    update_account_settings(&app, |settings| {
+        if let Some(path) = settings.profile_image_path.as_deref() {
+            let profile = PathBuf::from(path);
+            if profile.exists() {
+                fs::remove_file(profile).map_err(|e| {
+                    AppError::storage(format!("Failed to delete profile image: {}", e))
+                })?;
+            }
         }
-    }
-    settings.profile_image_path = None;
-    save_account_settings(&app, &settings)
+        settings.profile_image_path = None;
+        Ok(())
+    })
 }
 
+/// Stored session in its original shape; an unusable one comes back empty.
 #[tauri::command]
-pub fn get_user_preferences(app: tauri::AppHandle) -> UserPreferences {
-    load_preferences(&app)
+pub fn get_auth_session(app: tauri::AppHandle) -> AuthSession {
+    session_state(&app)
+        .session
+        .unwrap_or_else(default_auth_session)
 }
 
 #[tauri::command]
-pub fn save_user_preferences(
-    app: tauri::AppHandle,
-    preferences: UserPreferences,
-) -> Result<UserPreferences, String> {
-    save_preferences(&app, &preferences)?;
-    Ok(preferences)
+pub fn get_session_state(app: tauri::AppHandle) -> SessionState {
+    session_state(&app)
 }
 
 #[tauri::command]
-pub fn get_auth_session(app: tauri::AppHandle) -> AuthSession {
-    load_session(&app)
+pub fn save_auth_session(app: tauri::AppHandle, session: AuthSession) -> AppResult<()> {
+    save_session(&app, &session).map_err(AppError::storage)?;
+    entitlements::refresh_in_background(&app);
+    Ok(())
 }
 
 #[tauri::command]
-pub fn save_auth_session(app: tauri::AppHandle, session: AuthSession) -> Result<(), String> {
-    save_session(&app, &session)
+pub fn clear_auth_session(app: tauri::AppHandle) -> AppResult<()> {
+    clear_session(&app).map_err(AppError::storage)
 }
 
+/// Sign out and, unless `keep_local_data` is set, remove every per-user file
+/// the backend owns so the next person on a shared PC starts clean.
+/// `user_preferences.json` is device-level and is kept.
 #[tauri::command]
-pub fn clear_auth_session(app: tauri::AppHandle) -> Result<(), String> {
-    clear_session(&app)
-}
+pub fn logout(
+    app: tauri::AppHandle,
+    alarm_state: State<'_, Mutex<AlarmManagerState>>,
+    nickname_cache: State<'_, NicknameAvailabilityCache>,
+    options: Option<LogoutOptions>,
+) -> AppResult<LogoutReport> {
+    let options = options.unwrap_or_default();
+    clear_session(&app).map_err(AppError::storage)?;
+
+    let mut report = LogoutReport {
+        kept_local_data: options.keep_local_data,
+        cleared: vec![SESSION_FILE.to_string()],
+        failed: Vec::new(),
+    };
+    nickname_cache.clear();
 
-#[tauri::command]
-pub fn logout(app: tauri::AppHandle) -> Result<(), String> {
-    clear_session(&app)
+    if options.keep_local_data {
+        return Ok(report);
+    }
+
+    // The workspace being logged out of, to hand over from once it is gone.
+    let removed = {
+        let state = workspace::load_state(&app);
+        state
+            .current_workspace_id
+            .as_deref()
+            .and_then(|id| state.workspaces.iter().find(|ws| ws.id == id))
+            .map(workspace::Workspace::identity)
+    };
+
+    report.record(
+        "alarm_state",
+        alarm_state
+            .lock()
+            .map_err(|_| AppError::state_lock("alarm").message)
+            .and_then(|mut guard| alarm::reset_alarm_manager(&app, &mut guard)),
+    );
+    report.record("workspace_state", workspace::clear_local_state(&app));
+    report.record("workspace_sessions", workspace_session::clear_sessions(&app));
+    report.record("workspace_templates", workspace_template::clear_templates(&app));
+    report.record("account_settings", clear_local_account_data(&app));
+    report.record("backups", backup::clear_backups(&app));
+    report.record("entitlements_cache", entitlements::clear_cache(&app));
+    report.record("team_cache", team_cache::clear_cache(&app));
+    report.record("workspace_data", workspace_storage::clear_all(&app));
+
+    // Store the fresh default workspace and switch to it, so the alarm context
+    // and the windows drop the logged-out workspace.
+    match workspace::update_state(&app, |_| Ok(())) {
+        Ok(state) => {
+            if let Some(removed) = &removed {
+                workspace::notify_current_replaced(&app, removed, &state);
+            }
+        }
+        Err(e) => log::warn!(
+            "logout: failed to create the default workspace: {}",
+            e.message
+        ),
+    }
+
+    Ok(report)
 }
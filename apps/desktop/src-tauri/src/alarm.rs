@@ -1,11 +1,19 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager, State};
 
-const ALARM_STATE_FILE: &str = "alarm_state.json";
+use crate::config_store;
+use crate::error::{AppError, AppResult};
+use crate::migrations;
+use crate::preferences::{self, PreferencesChange};
+use crate::time::{self, now_unix};
+use crate::workspace::{self, WorkspaceChange, WorkspaceIdMap, WorkspaceIdentity};
+
+const ALARM_STATE_FILE: &str = migrations::ALARM_STATE.file;
+/// All-day tasks and the daily digest are announced at 09:00 local time.
+const MORNING_MINUTE: u32 = 9 * 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -31,6 +39,24 @@ pub struct AlarmRecord {
     pub last_triggered_at_unix: Option<i64>,
     pub created_at_unix: i64,
     pub updated_at_unix: i64,
+    #[serde(default)]
+    pub all_day: bool,
+    /// Local workspace for `workspace_id` (a server id), when known.
+    #[serde(default)]
+    pub workspace_local_id: Option<String>,
+    #[serde(default)]
+    pub workspace_name: Option<String>,
+    /// The workspace is archived or in the trash; the alarm neither fires nor
+    /// shows in the digest until it is restored.
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl AlarmRecord {
+    fn belongs_to(&self, identity: &WorkspaceIdentity) -> bool {
+        identity.server_id == Some(self.workspace_id)
+            || self.workspace_local_id.as_deref() == Some(identity.local_id.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +65,8 @@ pub struct AlarmManagerState {
     pub notifications_enabled: bool,
     #[serde(default)]
     pub alarms: Vec<AlarmRecord>,
+    #[serde(default)]
+    pub next_digest_at_unix: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +79,9 @@ pub struct TaskAlarmInput {
     pub reminder_minutes_before: Option<i64>,
     #[serde(default)]
     pub is_enabled: Option<bool>,
+    /// `start_at_unix` is any instant on the task's local date.
+    #[serde(default)]
+    pub all_day: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -61,13 +92,94 @@ pub struct AlarmTriggeredPayload {
     pub title: String,
     pub message: String,
     pub scheduled_start_at_unix: i64,
+    pub workspace_local_id: Option<String>,
+    pub workspace_name: Option<String>,
+    /// Whether the alarm's workspace is the one currently open.
+    pub in_current_workspace: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlarmDigestItem {
+    pub alarm_id: String,
+    pub task_id: i64,
+    pub workspace_id: i64,
+    pub title: String,
+    pub start_at_unix: i64,
+    pub all_day: bool,
+    pub workspace_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlarmDigestPayload {
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    pub message: String,
+    pub alarms: Vec<AlarmDigestItem>,
+}
+
+/// Device-level alarm behaviour that is carried over by settings export.
+/// Individual alarm records are not: they are rebuilt from tasks on sync.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlarmPolicies {
+    #[serde(default = "default_notifications_enabled")]
+    pub notifications_enabled: bool,
 }
 
-fn now_unix() -> i64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0)
+/// Preference values the scheduler depends on, kept current by the
+/// preferences service.
+#[derive(Debug, Clone)]
+pub struct AlarmContext {
+    pub language: String,
+    pub timezone: chrono_tz::Tz,
+    /// Minutes since local midnight, `None` when quiet hours are off.
+    pub quiet_hours: Option<(u32, u32)>,
+    pub quiet_on_days_off: bool,
+    pub daily_digest_enabled: bool,
+    pub working_hours: preferences::WorkingHours,
+    pub current_workspace: Option<WorkspaceIdentity>,
+}
+
+impl AlarmContext {
+    fn from_preferences(preferences: &preferences::UserPreferences) -> Self {
+        let quiet = &preferences.quiet_hours;
+        AlarmContext {
+            language: preferences.language.clone(),
+            timezone: time::parse_timezone(&preferences.timezone),
+            quiet_hours: quiet
+                .enabled
+                .then(|| {
+                    Some((
+                        preferences::parse_clock_time(&quiet.start)?,
+                        preferences::parse_clock_time(&quiet.end)?,
+                    ))
+                })
+                .flatten(),
+            quiet_on_days_off: quiet.include_days_off,
+            daily_digest_enabled: preferences.daily_digest_enabled,
+            working_hours: preferences.working_hours.clone(),
+            current_workspace: None,
+        }
+    }
+
+    /// End of the quiet window if `now` is inside it. With `include_days_off`
+    /// a day off is quiet until the window ends on the next working day.
+    fn quiet_until(&self, now: i64) -> Option<i64> {
+        let (start, end) = self.quiet_hours?;
+        let today = time::local_date(now, self.timezone);
+        if self.quiet_on_days_off && !time::is_working_day(today, &self.working_hours) {
+            let workday = time::next_working_day(today, &self.working_hours)?;
+            let until = time::local_time_to_unix(workday, end, self.timezone);
+            return (until > now).then_some(until);
+        }
+        time::is_in_daily_window(now, self.timezone, start, end)
+            .then(|| time::next_local_time(now, end, self.timezone))
+    }
+}
+
+fn alarm_context(app: &tauri::AppHandle) -> AlarmContext {
+    app.try_state::<Mutex<AlarmContext>>()
+        .and_then(|context| context.lock().ok().map(|c| c.clone()))
+        .unwrap_or_else(|| AlarmContext::from_preferences(&preferences::current_preferences(app)))
 }
 
 fn default_notifications_enabled() -> bool {
@@ -78,29 +190,12 @@ fn default_state() -> AlarmManagerState {
     AlarmManagerState {
         notifications_enabled: default_notifications_enabled(),
         alarms: Vec::new(),
+        next_digest_at_unix: None,
     }
 }
 
 fn alarm_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    app.path()
-        .app_config_dir()
-        .map(|dir| dir.join(ALARM_STATE_FILE))
-        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
-}
-
-fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
-    let raw = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&raw).ok()
-}
-
-fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
-    let json = serde_json::to_string_pretty(value)
-        .map_err(|e| format!("Failed to serialize json: {}", e))?;
-    fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))
+    config_store::config_path(app, ALARM_STATE_FILE)
 }
 
 pub fn load_alarm_manager(app: &tauri::AppHandle) -> AlarmManagerState {
@@ -108,25 +203,223 @@ pub fn load_alarm_manager(app: &tauri::AppHandle) -> AlarmManagerState {
         Ok(path) => path,
         Err(_) => return default_state(),
     };
-    read_json::<AlarmManagerState>(&path).unwrap_or_else(default_state)
+    config_store::read::<AlarmManagerState>(&path, &migrations::ALARM_STATE).unwrap_or_else(default_state)
 }
 
 fn save_alarm_manager(app: &tauri::AppHandle, state: &AlarmManagerState) -> Result<(), String> {
     let path = alarm_state_path(app)?;
-    write_json(&path, state)
+    config_store::write(&path, &migrations::ALARM_STATE, state)
+}
+
+/// Drop every alarm record, in memory and on disk. Used on logout.
+pub fn reset_alarm_manager(
+    app: &tauri::AppHandle,
+    state: &mut AlarmManagerState,
+) -> Result<(), String> {
+    *state = default_state();
+    config_store::remove(&alarm_state_path(app)?)
+}
+
+pub fn alarm_policies(state: &AlarmManagerState) -> AlarmPolicies {
+    AlarmPolicies {
+        notifications_enabled: state.notifications_enabled,
+    }
+}
+
+pub fn apply_alarm_policies(
+    app: &tauri::AppHandle,
+    state: &mut AlarmManagerState,
+    policies: &AlarmPolicies,
+) -> Result<(), String> {
+    state.notifications_enabled = policies.notifications_enabled;
+    save_alarm_manager(app, state)
+}
+
+fn alarm_message(language: &str, title: &str) -> String {
+    match language {
+        "en" => format!("It's time for {}.", title),
+        _ => format!("{} 일정 시간이 되었습니다.", title),
+    }
+}
+
+fn digest_message(language: &str, count: usize) -> String {
+    match (language, count) {
+        ("en", 0) => "Nothing scheduled today.".to_string(),
+        ("en", 1) => "1 alarm scheduled today.".to_string(),
+        ("en", n) => format!("{} alarms scheduled today.", n),
+        (_, 0) => "오늘 예정된 일정이 없습니다.".to_string(),
+        (_, n) => format!("오늘 예정된 알림이 {}개 있습니다.", n),
+    }
+}
+
+fn on_preferences_changed(app: &tauri::AppHandle, change: &PreferencesChange) {
+    if let Some(context) = app.try_state::<Mutex<AlarmContext>>() {
+        if let Ok(mut guard) = context.lock() {
+            let current_workspace = guard.current_workspace.take();
+            *guard = AlarmContext::from_preferences(&change.preferences);
+            guard.current_workspace = current_workspace;
+        }
+    }
+
+    let reschedule_digest =
+        change.changed("timezone") || change.changed("daily_digest_enabled");
+    if change.changed("notifications_enabled") || reschedule_digest {
+        if let Some(state) = app.try_state::<Mutex<AlarmManagerState>>() {
+            if let Ok(mut guard) = state.lock() {
+                guard.notifications_enabled = change.preferences.notifications_enabled;
+                if reschedule_digest {
+                    // Recomputed by the scheduler in the new timezone.
+                    guard.next_digest_at_unix = None;
+                }
+                if let Err(e) = save_alarm_manager(app, &guard) {
+                    log::warn!("alarm: failed to persist notification preference: {}", e);
+                }
+            }
+        }
+    }
+}
+
+/// Seed the scheduler context and follow preference changes.
+pub fn subscribe_to_preferences(app: &tauri::AppHandle) {
+    let current = preferences::current_preferences(app);
+    app.manage(Mutex::new(AlarmContext::from_preferences(&current)));
+
+    if let Some(service) = app.try_state::<preferences::PreferencesService>() {
+        service.subscribe(Box::new(on_preferences_changed));
+    }
+}
+
+fn on_workspace_changed(app: &tauri::AppHandle, change: &WorkspaceChange) {
+    if let WorkspaceChange::Switched { from, to } = change {
+        log::debug!(
+            "alarm: current workspace {:?} -> {}",
+            from.as_ref().map(|w| &w.local_id),
+            to.local_id
+        );
+        if let Some(context) = app.try_state::<Mutex<AlarmContext>>() {
+            if let Ok(mut guard) = context.lock() {
+                guard.current_workspace = Some(to.clone());
+            }
+        }
+        return;
+    }
+
+    let Some(state) = app.try_state::<Mutex<AlarmManagerState>>() else {
+        return;
+    };
+    let Ok(mut guard) = state.lock() else {
+        return;
+    };
+    match change {
+        WorkspaceChange::Deleted(identity) => {
+            let before = guard.alarms.len();
+            guard.alarms.retain(|alarm| !alarm.belongs_to(identity));
+            if guard.alarms.len() == before {
+                return;
+            }
+            log::info!(
+                "alarm: cleared {} alarms of deleted workspace {}",
+                before - guard.alarms.len(),
+                identity.local_id
+            );
+        }
+        WorkspaceChange::Renamed(identity) => {
+            let mut touched = false;
+            for alarm in guard.alarms.iter_mut().filter(|a| a.belongs_to(identity)) {
+                alarm.workspace_local_id = Some(identity.local_id.clone());
+                alarm.workspace_name = Some(identity.name.clone());
+                touched = true;
+            }
+            if !touched {
+                return;
+            }
+        }
+        WorkspaceChange::Deactivated(identity) => {
+            let mut paused = 0;
+            for alarm in guard.alarms.iter_mut().filter(|a| a.belongs_to(identity) && !a.paused) {
+                alarm.paused = true;
+                paused += 1;
+            }
+            if paused == 0 {
+                return;
+            }
+            log::info!("alarm: paused {} alarms of workspace {}", paused, identity.local_id);
+        }
+        WorkspaceChange::Reactivated(identity) => {
+            let now = now_unix();
+            let tz = alarm_context(app).timezone;
+            let mut resumed = 0;
+            for alarm in guard.alarms.iter_mut().filter(|a| a.belongs_to(identity) && a.paused) {
+                alarm.paused = false;
+                // Reminders missed while paused are not replayed.
+                if alarm.status != AlarmStatus::Dismissed
+                    && task_is_over(alarm.start_at_unix, alarm.all_day, now, tz)
+                {
+                    alarm.status = AlarmStatus::Dismissed;
+                    alarm.next_trigger_at_unix = None;
+                    alarm.is_enabled = false;
+                }
+                alarm.updated_at_unix = now;
+                resumed += 1;
+            }
+            if resumed == 0 {
+                return;
+            }
+            log::info!("alarm: resumed {} alarms of workspace {}", resumed, identity.local_id);
+        }
+        WorkspaceChange::Created(_) | WorkspaceChange::Switched { .. } => return,
+    }
+    if let Err(e) = save_alarm_manager(app, &guard) {
+        log::warn!("alarm: failed to persist workspace change: {}", e);
+    }
+}
+
+/// Track the current workspace and follow workspace renames and deletions.
+pub fn subscribe_to_workspaces(app: &tauri::AppHandle) {
+    let state = workspace::load_state(app);
+    let current = state
+        .current_workspace_id
+        .as_deref()
+        .and_then(|id| WorkspaceIdMap::new(&state).by_local_id(id).cloned());
+    if let Some(context) = app.try_state::<Mutex<AlarmContext>>() {
+        if let Ok(mut guard) = context.lock() {
+            guard.current_workspace = current;
+        }
+    }
+    workspace::subscribe(app, Box::new(on_workspace_changed));
 }
 
 fn alarm_id_for_task(workspace_id: i64, task_id: i64, start_at_unix: i64) -> String {
     format!("task:{}:{}:{}", workspace_id, task_id, start_at_unix)
 }
 
-fn build_alarm_from_input(input: &TaskAlarmInput, now: i64) -> AlarmRecord {
+/// All-day tasks have no start time and stay alive until their local day ends.
+fn task_is_over(start_at_unix: i64, all_day: bool, now: i64, tz: chrono_tz::Tz) -> bool {
+    if !all_day {
+        return start_at_unix <= now;
+    }
+    time::local_date(start_at_unix, tz)
+        .succ_opt()
+        .map(|next| time::start_of_local_day(next, tz))
+        .is_some_and(|day_end| day_end <= now)
+}
+
+fn build_alarm_from_input(input: &TaskAlarmInput, now: i64, tz: chrono_tz::Tz) -> AlarmRecord {
     let reminder = input.reminder_minutes_before.unwrap_or(10).max(0);
-    let trigger_at = input.start_at_unix - (reminder * 60);
     let enabled = input.is_enabled.unwrap_or(true);
     let alarm_id = alarm_id_for_task(input.workspace_id, input.task_id, input.start_at_unix);
 
-    if !enabled || input.start_at_unix <= now {
+    // All-day tasks have no start time: remind relative to 09:00 on the local
+    // date, and keep the alarm alive until that day is over.
+    let trigger_at = if input.all_day {
+        let date = time::local_date(input.start_at_unix, tz);
+        time::local_time_to_unix(date, MORNING_MINUTE, tz) - reminder * 60
+    } else {
+        input.start_at_unix - reminder * 60
+    };
+    let expired = task_is_over(input.start_at_unix, input.all_day, now, tz);
+
+    if !enabled || expired {
         return AlarmRecord {
             alarm_id,
             task_id: input.task_id,
@@ -141,6 +434,10 @@ fn build_alarm_from_input(input: &TaskAlarmInput, now: i64) -> AlarmRecord {
             last_triggered_at_unix: None,
             created_at_unix: now,
             updated_at_unix: now,
+            all_day: input.all_day,
+            workspace_local_id: None,
+            workspace_name: None,
+            paused: false,
         };
     }
 
@@ -158,27 +455,32 @@ fn build_alarm_from_input(input: &TaskAlarmInput, now: i64) -> AlarmRecord {
         last_triggered_at_unix: None,
         created_at_unix: now,
         updated_at_unix: now,
+        all_day: input.all_day,
+        workspace_local_id: None,
+        workspace_name: None,
+        paused: false,
     }
 }
 
+fn alarm_not_found(alarm_id: &str) -> AppError {
+    AppError::not_found("alarm_not_found", "Alarm not found")
+        .with_details(serde_json::json!({ "alarm_id": alarm_id }))
+}
+
 #[tauri::command]
 pub fn set_alarm_notifications_enabled(
     app: tauri::AppHandle,
     state: State<'_, Mutex<AlarmManagerState>>,
     enabled: bool,
-) -> Result<(), String> {
-    let mut guard = state
-        .lock()
-        .map_err(|_| "Failed to lock alarm state".to_string())?;
+) -> AppResult<()> {
+    let mut guard = state.lock().map_err(|_| AppError::state_lock("alarm"))?;
     guard.notifications_enabled = enabled;
-    save_alarm_manager(&app, &guard)
+    save_alarm_manager(&app, &guard).map_err(AppError::storage)
 }
 
 #[tauri::command]
-pub fn get_alarm_manager_state(state: State<'_, Mutex<AlarmManagerState>>) -> Result<AlarmManagerState, String> {
-    let guard = state
-        .lock()
-        .map_err(|_| "Failed to lock alarm state".to_string())?;
+pub fn get_alarm_manager_state(state: State<'_, Mutex<AlarmManagerState>>) -> AppResult<AlarmManagerState> {
+    let guard = state.lock().map_err(|_| AppError::state_lock("alarm"))?;
     Ok(guard.clone())
 }
 
@@ -187,14 +489,12 @@ pub fn clear_workspace_task_alarms(
     app: tauri::AppHandle,
     state: State<'_, Mutex<AlarmManagerState>>,
     workspace_id: i64,
-) -> Result<(), String> {
-    let mut guard = state
-        .lock()
-        .map_err(|_| "Failed to lock alarm state".to_string())?;
+) -> AppResult<()> {
+    let mut guard = state.lock().map_err(|_| AppError::state_lock("alarm"))?;
     guard
         .alarms
         .retain(|alarm| !(alarm.workspace_id == workspace_id && alarm.alarm_id.starts_with("task:")));
-    save_alarm_manager(&app, &guard)
+    save_alarm_manager(&app, &guard).map_err(AppError::storage)
 }
 
 #[tauri::command]
@@ -202,21 +502,26 @@ pub fn sync_task_alarms(
     app: tauri::AppHandle,
     state: State<'_, Mutex<AlarmManagerState>>,
     alarms: Vec<TaskAlarmInput>,
-) -> Result<usize, String> {
+) -> AppResult<usize> {
     let now = now_unix();
+    let tz = alarm_context(&app).timezone;
+    let workspaces = WorkspaceIdMap::load(&app);
     let incoming_workspace_ids: HashSet<i64> = alarms.iter().map(|a| a.workspace_id).collect();
 
     let mut incoming_ids: HashSet<String> = HashSet::new();
     let mut normalized: Vec<AlarmRecord> = Vec::new();
     for input in &alarms {
-        let record = build_alarm_from_input(input, now);
+        let mut record = build_alarm_from_input(input, now, tz);
+        if let Some(identity) = workspaces.by_server_id(input.workspace_id) {
+            record.workspace_local_id = Some(identity.local_id.clone());
+            record.workspace_name = Some(identity.name.clone());
+            record.paused = !workspaces.is_active(&identity.local_id);
+        }
         incoming_ids.insert(record.alarm_id.clone());
         normalized.push(record);
     }
 
-    let mut guard = state
-        .lock()
-        .map_err(|_| "Failed to lock alarm state".to_string())?;
+    let mut guard = state.lock().map_err(|_| AppError::state_lock("alarm"))?;
 
     // Keep existing alarms except task alarms in affected workspaces that are not in incoming ids.
     let mut kept: Vec<AlarmRecord> = guard
@@ -256,7 +561,7 @@ pub fn sync_task_alarms(
     }
 
     guard.alarms = kept;
-    save_alarm_manager(&app, &guard)?;
+    save_alarm_manager(&app, &guard).map_err(AppError::storage)?;
     Ok(guard.alarms.len())
 }
 
@@ -266,10 +571,8 @@ pub fn snooze_alarm(
     state: State<'_, Mutex<AlarmManagerState>>,
     alarm_id: String,
     minutes: i64,
-) -> Result<(), String> {
-    let mut guard = state
-        .lock()
-        .map_err(|_| "Failed to lock alarm state".to_string())?;
+) -> AppResult<()> {
+    let mut guard = state.lock().map_err(|_| AppError::state_lock("alarm"))?;
     let now = now_unix();
     let snooze_until = now + (minutes.max(1) * 60);
 
@@ -277,13 +580,13 @@ pub fn snooze_alarm(
         .alarms
         .iter_mut()
         .find(|a| a.alarm_id == alarm_id)
-        .ok_or_else(|| "Alarm not found".to_string())?;
+        .ok_or_else(|| alarm_not_found(&alarm_id))?;
 
     alarm.status = AlarmStatus::Snoozed;
     alarm.next_trigger_at_unix = Some(snooze_until);
     alarm.is_enabled = true;
     alarm.updated_at_unix = now;
-    save_alarm_manager(&app, &guard)
+    save_alarm_manager(&app, &guard).map_err(AppError::storage)
 }
 
 #[tauri::command]
@@ -291,23 +594,21 @@ pub fn dismiss_alarm(
     app: tauri::AppHandle,
     state: State<'_, Mutex<AlarmManagerState>>,
     alarm_id: String,
-) -> Result<(), String> {
-    let mut guard = state
-        .lock()
-        .map_err(|_| "Failed to lock alarm state".to_string())?;
+) -> AppResult<()> {
+    let mut guard = state.lock().map_err(|_| AppError::state_lock("alarm"))?;
     let now = now_unix();
 
     let alarm = guard
         .alarms
         .iter_mut()
         .find(|a| a.alarm_id == alarm_id)
-        .ok_or_else(|| "Alarm not found".to_string())?;
+        .ok_or_else(|| alarm_not_found(&alarm_id))?;
 
     alarm.status = AlarmStatus::Dismissed;
     alarm.next_trigger_at_unix = None;
     alarm.is_enabled = false;
     alarm.updated_at_unix = now;
-    save_alarm_manager(&app, &guard)
+    save_alarm_manager(&app, &guard).map_err(AppError::storage)
 }
 
 pub fn start_alarm_scheduler(app: AppHandle) {
@@ -316,13 +617,16 @@ pub fn start_alarm_scheduler(app: AppHandle) {
         let now = now_unix();
 
         let mut triggered: Vec<AlarmTriggeredPayload> = Vec::new();
+        let mut digest: Option<AlarmDigestPayload> = None;
         let mut should_save = false;
+        let context = alarm_context(&app);
+        let quiet_until = context.quiet_until(now);
 
         if let Some(state) = app.try_state::<Mutex<AlarmManagerState>>() {
             if let Ok(mut guard) = state.lock() {
                 if guard.notifications_enabled {
                     for alarm in guard.alarms.iter_mut() {
-                        if !alarm.is_enabled {
+                        if !alarm.is_enabled || alarm.paused {
                             continue;
                         }
                         if !matches!(alarm.status, AlarmStatus::Pending | AlarmStatus::Snoozed) {
@@ -333,6 +637,14 @@ pub fn start_alarm_scheduler(app: AppHandle) {
                             continue;
                         }
 
+                        // 방해 금지 시간에는 종료 시각까지 미룬다
+                        if let Some(until) = quiet_until {
+                            alarm.next_trigger_at_unix = Some(until);
+                            alarm.updated_at_unix = now;
+                            should_save = true;
+                            continue;
+                        }
+
                         alarm.status = AlarmStatus::Fired;
                         alarm.last_triggered_at_unix = Some(now);
                         alarm.updated_at_unix = now;
@@ -343,12 +655,40 @@ pub fn start_alarm_scheduler(app: AppHandle) {
                             task_id: alarm.task_id,
                             workspace_id: alarm.workspace_id,
                             title: alarm.title.clone(),
-                            message: format!("{} 일정 시간이 되었습니다.", alarm.title),
+                            message: alarm_message(&context.language, &alarm.title),
                             scheduled_start_at_unix: alarm.start_at_unix,
+                            workspace_local_id: alarm.workspace_local_id.clone(),
+                            workspace_name: alarm.workspace_name.clone(),
+                            in_current_workspace: context
+                                .current_workspace
+                                .as_ref()
+                                .is_some_and(|current| alarm.belongs_to(current)),
                         });
                     }
                 }
 
+                if context.daily_digest_enabled {
+                    match guard.next_digest_at_unix {
+                        None => {
+                            guard.next_digest_at_unix =
+                                Some(time::next_local_time(now, MORNING_MINUTE, context.timezone));
+                            should_save = true;
+                        }
+                        Some(at) if at <= now && quiet_until.is_none() => {
+                            let today = time::local_date(now, context.timezone);
+                            if guard.notifications_enabled
+                                && time::is_working_day(today, &context.working_hours)
+                            {
+                                digest = Some(build_digest(&guard, now, &context));
+                            }
+                            guard.next_digest_at_unix =
+                                Some(time::next_local_time(now, MORNING_MINUTE, context.timezone));
+                            should_save = true;
+                        }
+                        Some(_) => {}
+                    }
+                }
+
                 if should_save {
                     let _ = save_alarm_manager(&app, &guard);
                 }
@@ -358,5 +698,35 @@ pub fn start_alarm_scheduler(app: AppHandle) {
         for payload in triggered {
             let _ = app.emit("alarm://trigger", payload);
         }
+        if let Some(payload) = digest {
+            let _ = app.emit("alarm://digest", payload);
+        }
     });
 }
+
+/// Enabled alarms whose task falls on today's local date.
+fn build_digest(state: &AlarmManagerState, now: i64, context: &AlarmContext) -> AlarmDigestPayload {
+    let today = time::local_date(now, context.timezone);
+    let mut alarms: Vec<AlarmDigestItem> = state
+        .alarms
+        .iter()
+        .filter(|alarm| alarm.is_enabled && !alarm.paused && alarm.status != AlarmStatus::Dismissed)
+        .filter(|alarm| time::local_date(alarm.start_at_unix, context.timezone) == today)
+        .map(|alarm| AlarmDigestItem {
+            alarm_id: alarm.alarm_id.clone(),
+            task_id: alarm.task_id,
+            workspace_id: alarm.workspace_id,
+            title: alarm.title.clone(),
+            start_at_unix: alarm.start_at_unix,
+            all_day: alarm.all_day,
+            workspace_name: alarm.workspace_name.clone(),
+        })
+        .collect();
+    alarms.sort_by_key(|item| (!item.all_day, item.start_at_unix));
+
+    AlarmDigestPayload {
+        date: time::local_date_string(now, context.timezone),
+        message: digest_message(&context.language, alarms.len()),
+        alarms,
+    }
+}
@@ -0,0 +1,127 @@
+/// Minimal Pecal API client for backend-side calls.
+///
+/// Uses the access token from the stored auth session and the same headers
+/// the frontend `ApiClient` sends.
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+use tauri_plugin_http::reqwest;
+
+use crate::account;
+
+const DEFAULT_API_BASE_URL: &str = "https://pecal.site";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone)]
+pub enum ApiError {
+    /// Could not reach the server (no network, DNS, timeout).
+    Offline(String),
+    /// No stored access token, or the server rejected it.
+    Unauthorized,
+    Status(u16),
+    Decode(String),
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Offline(e) => write!(f, "Server unreachable: {}", e),
+            ApiError::Unauthorized => write!(f, "Not signed in"),
+            ApiError::Status(status) => write!(f, "Server responded with status {}", status),
+            ApiError::Decode(e) => write!(f, "Failed to decode server response: {}", e),
+        }
+    }
+}
+
+pub fn api_base_url() -> String {
+    let configured = std::env::var("PECAL_API_BASE_URL")
+        .ok()
+        .or_else(|| option_env!("VITE_API_BASE_URL").map(str::to_string))
+        .unwrap_or_default();
+    let trimmed = configured.trim().trim_end_matches('/');
+    if trimmed.is_empty() {
+        DEFAULT_API_BASE_URL.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: String,
+    access_token: Option<String>,
+}
+
+impl ApiClient {
+    pub fn new(base_url: impl Into<String>, access_token: Option<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            access_token,
+        }
+    }
+
+    /// Client for the configured server, authenticated as the signed-in user.
+    pub fn from_app(app: &tauri::AppHandle) -> Self {
+        let token = account::load_session(app)
+            .access_token
+            .filter(|t| !t.trim().is_empty());
+        Self::new(api_base_url(), token)
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> Result<reqwest::RequestBuilder, ApiError> {
+        let token = self.access_token.as_deref().ok_or(ApiError::Unauthorized)?;
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| ApiError::Offline(e.to_string()))?;
+        Ok(client
+            .request(method, format!("{}{}", self.base_url, path))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .header("X-Client-Platform", "desktop")
+            .header("X-App-Version", env!("CARGO_PKG_VERSION")))
+    }
+
+    async fn send<T: DeserializeOwned>(&self, builder: reqwest::RequestBuilder) -> Result<T, ApiError> {
+        let response = builder.send().await.map_err(|e| {
+            if e.is_connect() || e.is_timeout() || e.is_request() {
+                ApiError::Offline(e.to_string())
+            } else {
+                ApiError::Decode(e.to_string())
+            }
+        })?;
+
+        let status = response.status().as_u16();
+        if status == 401 {
+            return Err(ApiError::Unauthorized);
+        }
+        if !(200..300).contains(&status) {
+            return Err(ApiError::Status(status));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ApiError::Offline(e.to_string()))?;
+        serde_json::from_str(&body).map_err(|e| ApiError::Decode(e.to_string()))
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ApiError> {
+        let builder = self.request(reqwest::Method::GET, path)?;
+        self.send(builder).await
+    }
+
+    pub async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T, ApiError> {
+        let builder = self.request(reqwest::Method::POST, path)?.body(to_body(body)?);
+        self.send(builder).await
+    }
+
+    pub async fn patch<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T, ApiError> {
+        let builder = self.request(reqwest::Method::PATCH, path)?.body(to_body(body)?);
+        self.send(builder).await
+    }
+}
+
+fn to_body<B: Serialize>(body: &B) -> Result<String, ApiError> {
+    serde_json::to_string(body).map_err(|e| ApiError::Decode(e.to_string()))
+}
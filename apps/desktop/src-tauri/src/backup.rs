@@ -0,0 +1,394 @@
+/// Rolling local snapshots of the config files.
+///
+/// Snapshots live in `<app config dir>/backups/<id>/` together with a
+/// `manifest.json`. One is taken automatically per day and the newest
+/// `MAX_SNAPSHOTS` are kept. The auth session is never copied, so restoring
+/// an old snapshot cannot sign a previous user back in.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, State};
+
+use crate::account::AccountSettings;
+use crate::alarm::{self, AlarmManagerState};
+use crate::config_store;
+use crate::error::{AppError, AppResult};
+use crate::migrations::{self, Schema};
+use crate::position::WindowState;
+use crate::preferences::{self, PreferencesChangeSource, UserPreferences};
+use crate::time::now_unix;
+use crate::workspace::{self, WorkspaceState};
+use crate::workspace_session::{self, WorkspaceSessions};
+use crate::workspace_template::{self, WorkspaceTemplates};
+
+pub const BACKUP_DIR: &str = "backups";
+const MANIFEST_FILE: &str = "manifest.json";
+const PARTIAL_PREFIX: &str = ".partial-";
+const MAX_SNAPSHOTS: usize = 7;
+const SNAPSHOT_INTERVAL_SECS: i64 = 24 * 60 * 60;
+const SCHEDULER_TICK: Duration = Duration::from_secs(60 * 60);
+
+/// Files included in a snapshot.
+const BACKED_UP: &[&Schema] = &[
+    &migrations::USER_PREFERENCES,
+    &migrations::ACCOUNT_SETTINGS,
+    &migrations::WORKSPACE_STATE,
+    &migrations::ALARM_STATE,
+    &migrations::WINDOW_STATE,
+    &migrations::WORKSPACE_SESSIONS,
+    &migrations::WORKSPACE_TEMPLATES,
+];
+
+/// Serializes snapshot creation, pruning and restore between the scheduler
+/// thread and commands.
+static BACKUP_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupTrigger {
+    Scheduled,
+    Manual,
+    PreRestore,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub created_at_unix: i64,
+    pub app_version: String,
+    pub trigger: BackupTrigger,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupSnapshot {
+    pub id: String,
+    pub created_at_unix: i64,
+    pub app_version: String,
+    pub trigger: BackupTrigger,
+    pub files: Vec<String>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupRestoredPayload {
+    pub snapshot_id: String,
+    pub safety_snapshot_id: String,
+    pub restored_files: Vec<String>,
+    pub workspace_state: WorkspaceState,
+}
+
+/// Snapshot contents parsed and upgraded before anything is written.
+struct StagedRestore {
+    preferences: Option<UserPreferences>,
+    account_settings: Option<AccountSettings>,
+    workspace_state: Option<WorkspaceState>,
+    alarm_state: Option<AlarmManagerState>,
+    window_state: Option<WindowState>,
+    workspace_sessions: Option<WorkspaceSessions>,
+    workspace_templates: Option<WorkspaceTemplates>,
+}
+
+fn now_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+fn backups_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    config_store::config_path(app, BACKUP_DIR)
+}
+
+/// Also held while the data directory moves, which pauses the scheduler.
+pub fn lock_backups() -> AppResult<std::sync::MutexGuard<'static, ()>> {
+    BACKUP_LOCK.lock().map_err(|_| AppError::state_lock("backup"))
+}
+
+fn read_manifest(dir: &Path) -> Option<BackupManifest> {
+    let raw = fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Complete snapshots, newest first. Directories without a readable manifest
+/// (including interrupted ones) are ignored.
+fn list_snapshots(root: &Path) -> Vec<BackupSnapshot> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<BackupSnapshot> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            if id.starts_with(PARTIAL_PREFIX) {
+                return None;
+            }
+            let dir = entry.path();
+            let manifest = read_manifest(&dir)?;
+            Some(BackupSnapshot {
+                id,
+                created_at_unix: manifest.created_at_unix,
+                app_version: manifest.app_version,
+                trigger: manifest.trigger,
+                files: manifest.files,
+                size_bytes: dir_size(&dir),
+            })
+        })
+        .collect();
+    snapshots.sort_by(|a, b| {
+        b.created_at_unix
+            .cmp(&a.created_at_unix)
+            .then_with(|| b.id.cmp(&a.id))
+    });
+    snapshots
+}
+
+fn prune(root: &Path) {
+    for stale in list_snapshots(root).into_iter().skip(MAX_SNAPSHOTS) {
+        if let Err(e) = fs::remove_dir_all(root.join(&stale.id)) {
+            log::warn!("backup: failed to prune snapshot {}: {}", stale.id, e);
+        }
+    }
+    // Leftovers from a snapshot interrupted by a crash.
+    if let Ok(entries) = fs::read_dir(root) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(PARTIAL_PREFIX) {
+                let _ = fs::remove_dir_all(entry.path());
+            }
+        }
+    }
+}
+
+/// Copy the config files into a new snapshot. Written to a partial directory
+/// first and renamed, so a crash never leaves a half-written snapshot listed.
+/// Callers must hold `BACKUP_LOCK`.
+fn create_snapshot_locked(app: &tauri::AppHandle, trigger: BackupTrigger) -> AppResult<BackupSnapshot> {
+    let root = backups_root(app).map_err(AppError::storage)?;
+    let id = now_millis().to_string();
+    let partial = root.join(format!("{}{}", PARTIAL_PREFIX, id));
+    fs::create_dir_all(&partial)
+        .map_err(|e| AppError::storage(format!("Failed to create backup directory: {}", e)))?;
+
+    let mut files = Vec::new();
+    for schema in BACKED_UP {
+        let source = config_store::config_path(app, schema.file).map_err(AppError::storage)?;
+        if !source.exists() {
+            continue;
+        }
+        if let Err(e) = fs::copy(&source, partial.join(schema.file)) {
+            let _ = fs::remove_dir_all(&partial);
+            return Err(AppError::storage(format!(
+                "Failed to back up {}: {}",
+                schema.file, e
+            )));
+        }
+        files.push(schema.file.to_string());
+    }
+
+    let manifest = BackupManifest {
+        created_at_unix: now_unix(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        trigger,
+        files,
+    };
+    let written = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(partial.join(MANIFEST_FILE), json).map_err(|e| e.to_string()))
+        .and_then(|_| fs::rename(&partial, root.join(&id)).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        let _ = fs::remove_dir_all(&partial);
+        return Err(AppError::storage(format!("Failed to finish backup: {}", e)));
+    }
+
+    prune(&root);
+    log::info!("backup: created {:?} snapshot {}", trigger, id);
+    Ok(BackupSnapshot {
+        size_bytes: dir_size(&root.join(&id)),
+        id,
+        created_at_unix: manifest.created_at_unix,
+        app_version: manifest.app_version,
+        trigger: manifest.trigger,
+        files: manifest.files,
+    })
+}
+
+/// Take a scheduled snapshot if the newest one is older than a day.
+fn run_scheduled_backup(app: &tauri::AppHandle) -> AppResult<()> {
+    let _guard = lock_backups()?;
+    let root = backups_root(app).map_err(AppError::storage)?;
+    let latest = list_snapshots(&root).first().map(|s| s.created_at_unix);
+    if latest.is_some_and(|at| now_unix() - at < SNAPSHOT_INTERVAL_SECS) {
+        return Ok(());
+    }
+    create_snapshot_locked(app, BackupTrigger::Scheduled).map(|_| ())
+}
+
+pub fn start_backup_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        if let Err(e) = run_scheduled_backup(&app) {
+            log::warn!("backup: scheduled snapshot failed: {}", e);
+        }
+        std::thread::sleep(SCHEDULER_TICK);
+    });
+}
+
+/// Remove every snapshot. Used on logout, since snapshots hold per-user data.
+pub fn clear_backups(app: &tauri::AppHandle) -> Result<(), String> {
+    let _guard = BACKUP_LOCK
+        .lock()
+        .map_err(|_| "Failed to lock backup state".to_string())?;
+    let root = backups_root(app)?;
+    if root.exists() {
+        fs::remove_dir_all(&root).map_err(|e| format!("Failed to remove backups: {}", e))?;
+    }
+    Ok(())
+}
+
+fn stage_file<T: serde::de::DeserializeOwned>(
+    dir: &Path,
+    schema: &Schema,
+    files: &[String],
+) -> AppResult<Option<T>> {
+    if !files.iter().any(|f| f == schema.file) {
+        return Ok(None);
+    }
+    config_store::parse_file::<T>(&dir.join(schema.file), schema)
+        .map(Some)
+        .map_err(|reason| {
+            AppError::validation("backup_invalid", format!("Snapshot file is unusable: {}", reason))
+                .with_details(serde_json::json!({ "file": schema.file, "reason": reason }))
+        })
+}
+
+fn stage_restore(dir: &Path, files: &[String]) -> AppResult<StagedRestore> {
+    let preferences = stage_file::<UserPreferences>(dir, &migrations::USER_PREFERENCES, files)?
+        .map(|p| preferences::validate_preferences(&p))
+        .transpose()?;
+    Ok(StagedRestore {
+        preferences,
+        account_settings: stage_file(dir, &migrations::ACCOUNT_SETTINGS, files)?,
+        workspace_state: stage_file::<WorkspaceState>(dir, &migrations::WORKSPACE_STATE, files)?
+            .map(workspace::validate_state)
+            .transpose()?,
+        alarm_state: stage_file(dir, &migrations::ALARM_STATE, files)?,
+        window_state: stage_file(dir, &migrations::WINDOW_STATE, files)?,
+        workspace_sessions: stage_file(dir, &migrations::WORKSPACE_SESSIONS, files)?,
+        workspace_templates: stage_file(dir, &migrations::WORKSPACE_TEMPLATES, files)?,
+    })
+}
+
+fn write_staged<T: Serialize>(
+    app: &tauri::AppHandle,
+    schema: &Schema,
+    value: Option<&T>,
+    restored: &mut Vec<String>,
+) -> AppResult<()> {
+    let Some(value) = value else {
+        return Ok(());
+    };
+    let path = config_store::config_path(app, schema.file).map_err(AppError::storage)?;
+    config_store::write(&path, schema, value).map_err(AppError::storage)?;
+    restored.push(schema.file.to_string());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_backups(app: tauri::AppHandle) -> AppResult<Vec<BackupSnapshot>> {
+    let root = backups_root(&app).map_err(AppError::storage)?;
+    Ok(list_snapshots(&root))
+}
+
+#[tauri::command]
+pub fn create_backup(app: tauri::AppHandle) -> AppResult<BackupSnapshot> {
+    let _guard = lock_backups()?;
+    create_snapshot_locked(&app, BackupTrigger::Manual)
+}
+
+/// Swap a snapshot in while the app is running.
+///
+/// Every file is validated first, then the current files are saved as a
+/// `pre_restore` snapshot so the restore can itself be undone. The alarm
+/// state lock is held while files are replaced so the scheduler cannot write
+/// in between; managed state is reloaded from disk afterwards. The workspace
+/// state goes through `workspace::replace_state`, which takes its lock and
+/// notifies subscribers of the restored workspaces. Files missing from the
+/// snapshot are left as they are.
+#[tauri::command]
+pub fn restore_backup(
+    app: tauri::AppHandle,
+    alarm_state: State<'_, Mutex<AlarmManagerState>>,
+    snapshot_id: String,
+) -> AppResult<BackupRestoredPayload> {
+    let _guard = lock_backups()?;
+    let root = backups_root(&app).map_err(AppError::storage)?;
+    let snapshot_id = snapshot_id.trim().to_string();
+    let snapshot = list_snapshots(&root)
+        .into_iter()
+        .find(|s| s.id == snapshot_id)
+        .ok_or_else(|| {
+            AppError::not_found("backup_not_found", "Backup snapshot not found")
+                .with_details(serde_json::json!({ "snapshot_id": snapshot_id }))
+        })?;
+    let staged = stage_restore(&root.join(&snapshot.id), &snapshot.files)?;
+
+    let safety = create_snapshot_locked(&app, BackupTrigger::PreRestore)?;
+
+    let mut restored = Vec::new();
+    {
+        let mut alarms = alarm_state.lock().map_err(|_| AppError::state_lock("alarm"))?;
+        let files = &mut restored;
+        write_staged(&app, &migrations::ACCOUNT_SETTINGS, staged.account_settings.as_ref(), files)?;
+        write_staged(&app, &migrations::ALARM_STATE, staged.alarm_state.as_ref(), files)?;
+        write_staged(&app, &migrations::WINDOW_STATE, staged.window_state.as_ref(), files)?;
+        *alarms = alarm::load_alarm_manager(&app);
+    }
+
+    if let Some(templates) = staged.workspace_templates {
+        workspace_template::replace_templates(&app, templates)?;
+        restored.push(migrations::WORKSPACE_TEMPLATES.file.to_string());
+    }
+
+    // Before the workspace state, so switch events carry the restored session.
+    if let Some(sessions) = staged.workspace_sessions {
+        workspace_session::replace_sessions(&app, sessions)?;
+        restored.push(migrations::WORKSPACE_SESSIONS.file.to_string());
+    }
+
+    // After the alarm lock is released: the alarm subscriber takes it.
+    if let Some(workspace_state) = staged.workspace_state {
+        workspace::replace_state(&app, workspace_state)?;
+        restored.push(migrations::WORKSPACE_STATE.file.to_string());
+    }
+
+    // Goes through the preferences service last so subscribers (including
+    // the alarm context) see the restored alarm state.
+    if let Some(restored_preferences) = &staged.preferences {
+        preferences::apply_preferences(&app, restored_preferences, PreferencesChangeSource::Restore)?;
+        restored.push(migrations::USER_PREFERENCES.file.to_string());
+    }
+
+    let payload = BackupRestoredPayload {
+        snapshot_id: snapshot.id,
+        safety_snapshot_id: safety.id,
+        restored_files: restored,
+        workspace_state: workspace::load_state(&app),
+    };
+    if let Err(e) = app.emit("backup://restored", payload.clone()) {
+        log::warn!("backup: failed to emit restore event: {}", e);
+    }
+    Ok(payload)
+}
@@ -0,0 +1,604 @@
+/// Shared JSON config store for every file in the app config directory.
+///
+/// - Writes go to a temp file that is fsynced and renamed over the target.
+/// - The previous version is kept as `<file>.bak` when it was valid JSON.
+/// - A file that fails to parse is moved aside as `<file>.corrupt-<unix>` and
+///   the `.bak` is restored if possible, instead of silently resetting to
+///   defaults and overwriting the user's data on the next save.
+/// - Files are stored in a versioned envelope and upgraded on load through the
+///   migration registry in `migrations.rs`.
+/// - Every recovery is recorded and exposed through `get_config_load_issues`.
+/// - Read-modify-write cycles run under `with_lock`, an advisory `<file>.lock`
+///   that also serializes a second Pecal process started alongside this one.
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use crate::data_location;
+use crate::migrations::{self, MigrationError, Schema};
+use crate::time::now_unix;
+
+const BACKUP_SUFFIX: &str = ".bak";
+const CORRUPT_MARKER: &str = ".corrupt-";
+const MAX_RECORDED_ISSUES: usize = 50;
+const LOCK_SUFFIX: &str = ".lock";
+const LOCK_TIMEOUT: Duration = Duration::from_secs(3);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(20);
+/// A lock older than this was left behind by a crashed process.
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(30);
+
+static LOAD_ISSUES: Mutex<Vec<ConfigLoadIssue>> = Mutex::new(Vec::new());
+/// Gives every write its own temp file; unlocked writers share the pid.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigLoadIssueKind {
+    Unreadable,
+    Corrupt,
+    UnsupportedVersion,
+    MigrationFailed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigLoadIssue {
+    pub file: String,
+    pub kind: ConfigLoadIssueKind,
+    pub message: String,
+    pub quarantined_to: Option<String>,
+    pub restored_from_backup: bool,
+    pub occurred_at_unix: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockError {
+    /// Another writer held the lock for longer than the timeout.
+    Timeout { file: String, waited_ms: u64 },
+    Io(String),
+}
+
+impl std::fmt::Display for LockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockError::Timeout { file, waited_ms } => write!(
+                f,
+                "{} is locked by another writer (waited {} ms)",
+                file, waited_ms
+            ),
+            LockError::Io(e) => write!(f, "Failed to lock config file: {}", e),
+        }
+    }
+}
+
+/// Held while a config file is being read-modified-written. The lock file
+/// holds a nonce and is removed on drop only if it still carries it: a holder
+/// that overran `LOCK_STALE_AFTER` may have had its lock broken and taken.
+struct ConfigLock {
+    path: PathBuf,
+    nonce: String,
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        match fs::read_to_string(&self.path) {
+            Ok(owner) if owner.ends_with(&self.nonce) => {
+                if let Err(e) = fs::remove_file(&self.path) {
+                    log::warn!("config: failed to release {}: {}", self.path.display(), e);
+                }
+            }
+            _ => log::warn!("config: {} was broken while held", self.path.display()),
+        }
+    }
+}
+
+/// Contents of the lock when it is old enough to be considered stale.
+fn stale_owner(lock_path: &Path) -> Option<String> {
+    let age = fs::metadata(lock_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+    if age <= LOCK_STALE_AFTER {
+        return None;
+    }
+    fs::read_to_string(lock_path).ok()
+}
+
+/// Move a stale lock out of the way. Renaming is atomic, so of several
+/// waiters only one moves a given file. A waiter that lost the race and moved
+/// a lock taken in the meantime puts it back.
+fn break_stale_lock(lock_path: &Path, stale_owner: &str, nonce: &str) {
+    let moved = sibling(lock_path, &format!(".stale-{}", nonce));
+    if fs::rename(lock_path, &moved).is_err() {
+        return;
+    }
+    if fs::read_to_string(&moved).is_ok_and(|owner| owner == stale_owner) {
+        log::warn!("config: broke stale lock {}", lock_path.display());
+    } else if let Err(e) = fs::hard_link(&moved, lock_path) {
+        log::warn!("config: failed to put back {}: {}", lock_path.display(), e);
+    }
+    let _ = fs::remove_file(&moved);
+}
+
+fn acquire_lock(path: &Path, timeout: Duration) -> Result<ConfigLock, LockError> {
+    let lock_path = sibling(path, LOCK_SUFFIX);
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LockError::Io(e.to_string()))?;
+    }
+
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let started = Instant::now();
+    loop {
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_path)
+        {
+            Ok(mut file) => {
+                // Pid and time are only for diagnosing a stuck lock by hand.
+                if let Err(e) = write!(file, "{} {} {}", std::process::id(), now_unix(), nonce) {
+                    let _ = fs::remove_file(&lock_path);
+                    return Err(LockError::Io(e.to_string()));
+                }
+                return Ok(ConfigLock {
+                    path: lock_path,
+                    nonce,
+                });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                if let Some(owner) = stale_owner(&lock_path) {
+                    break_stale_lock(&lock_path, &owner, &nonce);
+                    continue;
+                }
+                if started.elapsed() >= timeout {
+                    return Err(LockError::Timeout {
+                        file: file_label(path),
+                        waited_ms: started.elapsed().as_millis() as u64,
+                    });
+                }
+                std::thread::sleep(LOCK_RETRY_INTERVAL);
+            }
+            Err(e) => return Err(LockError::Io(e.to_string())),
+        }
+    }
+}
+
+/// Run `f` while holding the advisory lock for `path`. Use it around every
+/// load → modify → save cycle so concurrent writers, in this process or
+/// another, cannot overwrite each other's changes.
+pub fn with_lock<R>(path: &Path, f: impl FnOnce() -> R) -> Result<R, LockError> {
+    with_lock_timeout(path, LOCK_TIMEOUT, f)
+}
+
+pub fn with_lock_timeout<R>(
+    path: &Path,
+    timeout: Duration,
+    f: impl FnOnce() -> R,
+) -> Result<R, LockError> {
+    let _lock = acquire_lock(path, timeout)?;
+    Ok(f())
+}
+
+/// `with_lock` for several files at once, taken in the given order. Used to
+/// keep every writer out while the data directory moves.
+pub fn with_locks<R>(paths: &[PathBuf], f: impl FnOnce() -> R) -> Result<R, LockError> {
+    let _locks = paths
+        .iter()
+        .map(|path| acquire_lock(path, LOCK_TIMEOUT))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(f())
+}
+
+/// Path of a config file in the active data directory (see `data_location`).
+pub fn config_path(app: &tauri::AppHandle, filename: &str) -> Result<PathBuf, String> {
+    data_location::data_path(app, filename)
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, BACKUP_SUFFIX)
+}
+
+fn file_label(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+fn record_issue(issue: ConfigLoadIssue) {
+    log::error!(
+        "config: {} is {:?}: {} (quarantined: {:?}, restored from backup: {})",
+        issue.file,
+        issue.kind,
+        issue.message,
+        issue.quarantined_to,
+        issue.restored_from_backup
+    );
+    if let Ok(mut issues) = LOAD_ISSUES.lock() {
+        issues.push(issue);
+        let overflow = issues.len().saturating_sub(MAX_RECORDED_ISSUES);
+        issues.drain(..overflow);
+    }
+}
+
+fn decode<T: DeserializeOwned>(
+    raw: &str,
+    schema: &Schema,
+) -> Result<(T, u32), (ConfigLoadIssueKind, String)> {
+    let document: serde_json::Value =
+        serde_json::from_str(raw).map_err(|e| (ConfigLoadIssueKind::Corrupt, e.to_string()))?;
+    let (data, stored_version) = migrations::upgrade(schema, document).map_err(|e| {
+        let kind = match e {
+            MigrationError::UnsupportedVersion { .. } => ConfigLoadIssueKind::UnsupportedVersion,
+            MigrationError::Failed { .. } => ConfigLoadIssueKind::MigrationFailed,
+        };
+        (kind, e.to_string())
+    })?;
+    let value = serde_json::from_value(data)
+        .map_err(|e| (ConfigLoadIssueKind::Corrupt, e.to_string()))?;
+    Ok((value, stored_version))
+}
+
+/// Parse and upgrade a file without any recovery side effects.
+pub fn parse_file<T: DeserializeOwned>(path: &Path, schema: &Schema) -> Result<T, String> {
+    let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    decode(&raw, schema)
+        .map(|(value, _)| value)
+        .map_err(|(_, message)| message)
+}
+
+fn quarantine(path: &Path) -> Option<PathBuf> {
+    let target = sibling(path, &format!("{}{}", CORRUPT_MARKER, now_unix()));
+    match fs::rename(path, &target) {
+        Ok(()) => Some(target),
+        Err(e) => {
+            log::error!("config: failed to quarantine {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Read a config file, upgrading older schema versions in place. Returns
+/// `None` when the file does not exist or could not be recovered; callers
+/// fall back to their defaults in that case.
+pub fn read<T: DeserializeOwned + Serialize>(path: &Path, schema: &Schema) -> Option<T> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            record_issue(ConfigLoadIssue {
+                file: file_label(path),
+                kind: ConfigLoadIssueKind::Unreadable,
+                message: e.to_string(),
+                quarantined_to: None,
+                restored_from_backup: false,
+                occurred_at_unix: now_unix(),
+            });
+            return None;
+        }
+    };
+
+    let (kind, message) = match decode::<T>(&raw, schema) {
+        Ok((value, stored_version)) => {
+            if stored_version < schema.version {
+                log::info!(
+                    "config: migrated {} from v{} to v{}",
+                    schema.file,
+                    stored_version,
+                    schema.version
+                );
+                // The pre-migration file is kept as `.bak` by `write`.
+                if let Err(e) = write(path, schema, &value) {
+                    log::warn!("config: failed to persist migrated {}: {}", schema.file, e);
+                }
+            }
+            return Some(value);
+        }
+        Err(issue) => issue,
+    };
+
+    // A newer app version owns this file; leave it for that version. `write`
+    // refuses to replace it, so the defaults callers fall back to stay in memory.
+    if kind == ConfigLoadIssueKind::UnsupportedVersion {
+        record_issue(ConfigLoadIssue {
+            file: file_label(path),
+            kind,
+            message,
+            quarantined_to: None,
+            restored_from_backup: false,
+            occurred_at_unix: now_unix(),
+        });
+        return None;
+    }
+
+    let quarantined_to = quarantine(path);
+    let restored = parse_file::<T>(&backup_path(path), schema)
+        .ok()
+        .filter(|value| {
+            // Put the last good version back in place so the next read is clean.
+            match write_atomic(path, schema, value) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::error!("config: failed to restore backup of {}: {}", path.display(), e);
+                    false
+                }
+            }
+        });
+
+    record_issue(ConfigLoadIssue {
+        file: file_label(path),
+        kind,
+        message,
+        quarantined_to: quarantined_to.map(|p| p.to_string_lossy().to_string()),
+        restored_from_backup: restored.is_some(),
+        occurred_at_unix: now_unix(),
+    });
+    restored
+}
+
+fn write_atomic<T: Serialize>(path: &Path, schema: &Schema, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let data =
+        serde_json::to_value(value).map_err(|e| format!("Failed to serialize json: {}", e))?;
+    let json = serde_json::to_string_pretty(&migrations::wrap(schema, data))
+        .map_err(|e| format!("Failed to serialize json: {}", e))?;
+
+    let tmp = sibling(
+        path,
+        &format!(
+            ".tmp-{}-{}",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ),
+    );
+    let written = fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(json.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("Failed to write file: {}", e));
+    }
+    Ok(())
+}
+
+/// Write a config file atomically, keeping the current version as `.bak` if
+/// it is valid JSON. A file stored by a newer schema version is never
+/// replaced: this app version could only have read defaults in its place.
+pub fn write<T: Serialize>(path: &Path, schema: &Schema, value: &T) -> Result<(), String> {
+    let current = fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok());
+    if let Some(current) = current {
+        let stored_version = migrations::stored_version(&current);
+        if stored_version > schema.version {
+            return Err(format!(
+                "{} was saved by a newer version of Pecal (schema v{}, supported v{}); not overwriting it",
+                file_label(path),
+                stored_version,
+                schema.version
+            ));
+        }
+        if let Err(e) = fs::copy(path, backup_path(path)) {
+            log::warn!("config: failed to back up {}: {}", path.display(), e);
+        }
+    }
+    write_atomic(path, schema, value)
+}
+
+/// `write` a whole file under its lock. The path is resolved once the lock
+/// is held: the data directory may have moved while waiting for it.
+pub fn write_locked<T: Serialize>(
+    app: &tauri::AppHandle,
+    schema: &Schema,
+    value: &T,
+) -> Result<(), String> {
+    let path = config_path(app, schema.file)?;
+    with_lock(&path, || write(&config_path(app, schema.file)?, schema, value))
+        .map_err(|e| e.to_string())?
+}
+
+/// Remove a config file together with its backup and quarantined copies.
+pub fn remove(path: &Path) -> Result<(), String> {
+    let label = file_label(path);
+    let quarantine_prefix = format!("{}{}", label, CORRUPT_MARKER);
+    let mut targets = vec![path.to_path_buf(), backup_path(path)];
+    if let Some(entries) = path.parent().and_then(|dir| fs::read_dir(dir).ok()) {
+        targets.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with(&quarantine_prefix))
+                .map(|entry| entry.path()),
+        );
+    }
+
+    for target in targets {
+        if target.exists() {
+            fs::remove_file(&target)
+                .map_err(|e| format!("Failed to remove {}: {}", file_label(&target), e))?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_config_load_issues() -> Vec<ConfigLoadIssue> {
+    LOAD_ISSUES
+        .lock()
+        .map(|issues| issues.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTER: Schema = Schema {
+        file: "counter.json",
+        version: 0,
+        migrations: &[],
+    };
+
+    fn temp_config(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pecal-config-store-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(COUNTER.file)
+    }
+
+    #[test]
+    fn concurrent_writers_do_not_lose_updates() {
+        let path = temp_config("concurrent");
+        write(&path, &COUNTER, &0u32).unwrap();
+
+        let writers: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        with_lock(&path, || {
+                            let value = read::<u32>(&path, &COUNTER).unwrap();
+                            // Widen the race window an unlocked cycle would lose.
+                            std::thread::yield_now();
+                            write(&path, &COUNTER, &(value + 1)).unwrap();
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!(read::<u32>(&path, &COUNTER), Some(200));
+        assert!(!sibling(&path, LOCK_SUFFIX).exists());
+    }
+
+    #[test]
+    fn unlocked_writers_do_not_share_a_temp_file() {
+        let path = temp_config("unlocked");
+        let writers: Vec<_> = (0..8u32)
+            .map(|n| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        write(&path, &COUNTER, &n).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert!(read::<u32>(&path, &COUNTER).is_some_and(|n| n < 8));
+    }
+
+    #[test]
+    fn held_lock_times_out() {
+        let path = temp_config("timeout");
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+
+        let holder = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                with_lock(&path, || {
+                    locked_tx.send(()).unwrap();
+                    release_rx.recv().unwrap();
+                })
+                .unwrap();
+            })
+        };
+        locked_rx.recv().unwrap();
+
+        let result = with_lock_timeout(&path, Duration::from_millis(100), || ());
+        assert!(matches!(result, Err(LockError::Timeout { .. })));
+
+        release_tx.send(()).unwrap();
+        holder.join().unwrap();
+        assert!(with_lock_timeout(&path, Duration::from_millis(100), || ()).is_ok());
+    }
+
+    #[test]
+    fn stale_lock_is_broken() {
+        let path = temp_config("stale");
+        let lock_path = sibling(&path, LOCK_SUFFIX);
+        let file = fs::File::create(&lock_path).unwrap();
+        file.set_modified(SystemTime::now() - LOCK_STALE_AFTER * 2)
+            .unwrap();
+        drop(file);
+
+        assert!(with_lock_timeout(&path, Duration::from_millis(100), || ()).is_ok());
+        assert!(!lock_path.exists());
+    }
+
+    #[test]
+    fn release_keeps_a_lock_taken_over_by_another_writer() {
+        let path = temp_config("taken-over");
+        let lock_path = sibling(&path, LOCK_SUFFIX);
+        with_lock(&path, || fs::write(&lock_path, "1 0 other-nonce").unwrap()).unwrap();
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), "1 0 other-nonce");
+    }
+
+    #[test]
+    fn breaking_puts_back_a_lock_taken_in_the_meantime() {
+        let path = temp_config("break-race");
+        let lock_path = sibling(&path, LOCK_SUFFIX);
+        fs::write(&lock_path, "2 0 fresh-nonce").unwrap();
+
+        // Judged stale while it still held an older owner.
+        break_stale_lock(&lock_path, "1 0 stale-nonce", "breaker");
+        assert_eq!(fs::read_to_string(&lock_path).unwrap(), "2 0 fresh-nonce");
+        assert!(!sibling(&lock_path, ".stale-breaker").exists());
+    }
+
+    #[test]
+    fn newer_schema_is_left_in_place() {
+        let path = temp_config("newer");
+        let raw = r#"{ "schema_version": 7, "data": 5 }"#;
+        fs::write(&path, raw).unwrap();
+        fs::write(backup_path(&path), r#"{ "schema_version": 0, "data": 3 }"#).unwrap();
+
+        assert_eq!(read::<u32>(&path, &COUNTER), None);
+        assert_eq!(fs::read_to_string(&path).unwrap(), raw);
+        let quarantined = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .any(|entry| entry.file_name().to_string_lossy().contains(CORRUPT_MARKER));
+        assert!(!quarantined);
+    }
+
+    #[test]
+    fn save_after_newer_schema_read_leaves_file_intact() {
+        let path = temp_config("newer-save");
+        let raw = r#"{ "schema_version": 7, "data": 5 }"#;
+        fs::write(&path, raw).unwrap();
+
+        let value = read::<u32>(&path, &COUNTER).unwrap_or_default();
+        assert!(write(&path, &COUNTER, &value).is_err());
+        assert!(with_lock(&path, || write(&path, &COUNTER, &(value + 1)))
+            .unwrap()
+            .is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), raw);
+        assert!(!backup_path(&path).exists());
+    }
+}
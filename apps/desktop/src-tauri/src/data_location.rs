@@ -0,0 +1,371 @@
+/// Data location resolver — decides which directory holds the config files.
+///
+/// - Portable: a `pecal.portable` marker next to the executable puts the data
+///   in `data/` beside it (e.g. on a USB stick). Takes precedence over
+///   everything else.
+/// - Custom: a user-chosen directory, recorded in `data_location.json` inside
+///   the default config dir (the only file that always stays there).
+/// - Default: Tauri's app config dir.
+///
+/// Resolved once and cached; `move_data_directory` moves the files and
+/// switches the cache.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{Manager, State};
+
+use crate::account;
+use crate::alarm::AlarmManagerState;
+use crate::backup;
+use crate::config_store;
+use crate::error::{AppError, AppResult};
+use crate::migrations;
+use crate::preferences::PreferencesService;
+use crate::workspace_storage;
+
+const PORTABLE_MARKER: &str = "pecal.portable";
+const PORTABLE_DATA_DIR: &str = "data";
+
+static LOCATION: Mutex<Option<DataLocation>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DataMode {
+    Default,
+    Portable,
+    Custom,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataLocation {
+    pub mode: DataMode,
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataLocationPointer {
+    #[serde(default)]
+    pub custom_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataLocationInfo {
+    pub mode: DataMode,
+    pub dir: String,
+    pub default_dir: String,
+    /// Files that could not be removed from the previous directory after a move.
+    pub left_behind: Vec<String>,
+}
+
+fn default_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+}
+
+fn portable_dir() -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    exe_dir
+        .join(PORTABLE_MARKER)
+        .exists()
+        .then(|| exe_dir.join(PORTABLE_DATA_DIR))
+}
+
+fn pointer_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    default_dir(app).map(|dir| dir.join(migrations::DATA_LOCATION.file))
+}
+
+fn resolve(app: &tauri::AppHandle) -> Result<DataLocation, String> {
+    if let Some(dir) = portable_dir() {
+        return Ok(DataLocation {
+            mode: DataMode::Portable,
+            dir,
+        });
+    }
+
+    let custom = config_store::read::<DataLocationPointer>(
+        &pointer_path(app)?,
+        &migrations::DATA_LOCATION,
+    )
+    .and_then(|pointer| pointer.custom_dir)
+    .map(PathBuf::from)
+    .filter(|dir| dir.is_absolute());
+    if let Some(dir) = custom {
+        return Ok(DataLocation {
+            mode: DataMode::Custom,
+            dir,
+        });
+    }
+
+    Ok(DataLocation {
+        mode: DataMode::Default,
+        dir: default_dir(app)?,
+    })
+}
+
+pub fn current_location(app: &tauri::AppHandle) -> Result<DataLocation, String> {
+    let mut cached = LOCATION
+        .lock()
+        .map_err(|_| "Failed to lock data location".to_string())?;
+    if let Some(location) = cached.as_ref() {
+        return Ok(location.clone());
+    }
+    let location = resolve(app)?;
+    log::info!(
+        "data: using {:?} data dir {}",
+        location.mode,
+        location.dir.display()
+    );
+    *cached = Some(location.clone());
+    Ok(location)
+}
+
+/// Path of a file or directory inside the active data directory.
+pub fn data_path(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    current_location(app).map(|location| location.dir.join(name))
+}
+
+/// Config files moved with the data dir, including their `.bak` and
+/// quarantined copies. Plugin files in the default dir are left alone.
+const DATA_FILES: &[&migrations::Schema] = &[
+    &migrations::ACCOUNT_SETTINGS,
+    &migrations::USER_PREFERENCES,
+    &migrations::AUTH_SESSION,
+    &migrations::WORKSPACE_STATE,
+    &migrations::ALARM_STATE,
+    &migrations::WINDOW_STATE,
+    &migrations::WORKSPACE_SESSIONS,
+    &migrations::WORKSPACE_TEMPLATES,
+    &migrations::ENTITLEMENTS_CACHE,
+    &migrations::TEAM_CACHE,
+];
+const DATA_DIRS: &[&str] = &[
+    account::PROFILE_IMAGE_DIR,
+    backup::BACKUP_DIR,
+    workspace_storage::WORKSPACE_DATA_DIR,
+];
+
+/// Lock and temp files are never moved; they belong to an in-flight write.
+fn is_movable(name: &str) -> bool {
+    let owned = DATA_DIRS.contains(&name)
+        || DATA_FILES.iter().any(|schema| name.starts_with(schema.file));
+    owned && !name.ends_with(".lock") && !name.contains(".tmp-")
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let entries = fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+    for entry in entries.flatten() {
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if source.is_dir() {
+            copy_dir(&source, &target)?;
+        } else {
+            fs::copy(&source, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy the Pecal entries at the top of `from` into `to`, collecting what was
+/// copied so it can be removed afterwards.
+fn copy_tree(from: &Path, to: &Path, copied: &mut Vec<PathBuf>) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let entries = fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !is_movable(&name) {
+            continue;
+        }
+        let source = entry.path();
+        let target = to.join(&name);
+        if source.is_dir() {
+            copy_dir(&source, &target)?;
+        } else {
+            fs::copy(&source, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", name, e))?;
+        }
+        copied.push(source);
+    }
+    Ok(())
+}
+
+fn is_empty_dir(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(true)
+}
+
+fn info(app: &tauri::AppHandle, location: &DataLocation, left_behind: Vec<String>) -> AppResult<DataLocationInfo> {
+    Ok(DataLocationInfo {
+        mode: location.mode,
+        dir: location.dir.to_string_lossy().to_string(),
+        default_dir: default_dir(app)
+            .map_err(AppError::storage)?
+            .to_string_lossy()
+            .to_string(),
+        left_behind,
+    })
+}
+
+#[tauri::command]
+pub fn get_data_location(app: tauri::AppHandle) -> AppResult<DataLocationInfo> {
+    let location = current_location(&app).map_err(AppError::storage)?;
+    info(&app, &location, Vec::new())
+}
+
+/// Copy the data files to `target`, switch to it and remove the old copies.
+/// Returns the new location and the files that could not be removed.
+fn switch_to(
+    app: &tauri::AppHandle,
+    current: &DataLocation,
+    target: PathBuf,
+    target_is_default: bool,
+) -> AppResult<(DataLocation, Vec<String>)> {
+    let mut copied = Vec::new();
+    if current.dir.exists() {
+        if let Err(e) = copy_tree(&current.dir, &target, &mut copied) {
+            if !target_is_default {
+                let _ = fs::remove_dir_all(&target);
+            }
+            return Err(AppError::storage(e));
+        }
+    }
+
+    let pointer = DataLocationPointer {
+        custom_dir: (!target_is_default).then(|| target.to_string_lossy().to_string()),
+    };
+    let pointer_file = pointer_path(app).map_err(AppError::storage)?;
+    config_store::write(&pointer_file, &migrations::DATA_LOCATION, &pointer)
+        .map_err(AppError::storage)?;
+
+    let location = DataLocation {
+        mode: if target_is_default {
+            DataMode::Default
+        } else {
+            DataMode::Custom
+        },
+        dir: target,
+    };
+    *LOCATION.lock().map_err(|_| AppError::state_lock("data location"))? = Some(location.clone());
+
+    // The stored profile image path is absolute and points into the old dir.
+    if account::load_account_settings(&app).profile_image_path.is_some() {
+        account::update_account_settings(app, |settings| {
+            if let Some(rebased) = settings
+                .profile_image_path
+                .as_deref()
+                .and_then(|p| Path::new(p).strip_prefix(&current.dir).ok())
+                .map(|relative| location.dir.join(relative))
+            {
+                settings.profile_image_path = Some(rebased.to_string_lossy().to_string());
+            }
+            Ok(())
+        })?;
+    }
+
+    let mut left_behind = Vec::new();
+    for source in copied {
+        let removed = if source.is_dir() {
+            fs::remove_dir_all(&source)
+        } else {
+            fs::remove_file(&source)
+        };
+        if let Err(e) = removed {
+            log::warn!("data: failed to remove {}: {}", source.display(), e);
+            left_behind.push(source.to_string_lossy().to_string());
+        }
+    }
+    Ok((location, left_behind))
+}
+
+/// Move every data file to `target_dir` (or back to the default dir when
+/// omitted) and switch to it. The target must be empty or missing. Files are
+/// copied first and the old copies removed only after the switch.
+#[tauri::command]
+pub fn move_data_directory(
+    app: tauri::AppHandle,
+    alarm_state: State<'_, Mutex<AlarmManagerState>>,
+    target_dir: Option<String>,
+) -> AppResult<DataLocationInfo> {
+    let current = current_location(&app).map_err(AppError::storage)?;
+    if current.mode == DataMode::Portable {
+        return Err(AppError::conflict(
+            "portable_mode_active",
+            "The data directory cannot be moved in portable mode",
+        ));
+    }
+
+    let default = default_dir(&app).map_err(AppError::storage)?;
+    let target = match target_dir.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => default.clone(),
+    };
+    if !target.is_absolute() {
+        return Err(AppError::validation(
+            "data_dir_not_absolute",
+            "Data directory must be an absolute path",
+        ));
+    }
+    if target == current.dir {
+        return info(&app, &current, Vec::new());
+    }
+    if target.starts_with(&current.dir) || current.dir.starts_with(&target) {
+        return Err(AppError::conflict(
+            "data_dir_nested",
+            "Data directory cannot be inside the current one or contain it",
+        ));
+    }
+    let target_is_default = target == default;
+    // The default dir always holds the pointer file and plugin state, so only
+    // require it to be free of Pecal data files.
+    let occupied = if target_is_default {
+        fs::read_dir(&target)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .any(|e| is_movable(&e.file_name().to_string_lossy()))
+            })
+            .unwrap_or(false)
+    } else {
+        !is_empty_dir(&target)
+    };
+    if occupied {
+        return Err(AppError::conflict(
+            "data_dir_not_empty",
+            "Data directory must be empty",
+        )
+        .with_details(serde_json::json!({ "dir": target.to_string_lossy() })));
+    }
+
+    // Keep every writer out of the old directory until the files are copied,
+    // the location is switched and the old copies are gone: the backup
+    // scheduler and restores (backup lock, taken before the alarm state like
+    // `restore_backup` does), the alarm scheduler, the preferences watcher and
+    // every config file writer (config locks). Writers waiting on a config
+    // lock resolve their path again and land in the new directory.
+    let _backups = backup::lock_backups()?;
+    let _alarms = alarm_state.lock().map_err(|_| AppError::state_lock("alarm"))?;
+    let lock_paths: Vec<PathBuf> = DATA_FILES
+        .iter()
+        .map(|schema| current.dir.join(schema.file))
+        .collect();
+    let moved = || {
+        config_store::with_locks(&lock_paths, || {
+            switch_to(&app, &current, target, target_is_default)
+        })?
+    };
+    let (location, left_behind) = match app.try_state::<PreferencesService>() {
+        Some(service) => service.pause_watcher(&app, moved),
+        None => moved(),
+    }?;
+    // After the locks are released, so their files are gone too.
+    if current.mode == DataMode::Custom && is_empty_dir(&current.dir) {
+        let _ = fs::remove_dir(&current.dir);
+    }
+
+    log::info!("data: moved data dir to {}", location.dir.display());
+    info(&app, &location, left_behind)
+}
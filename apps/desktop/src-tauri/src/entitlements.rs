@@ -0,0 +1,452 @@
+/// Plan limits for the signed-in user.
+///
+/// Plan definitions come from `GET /api/plans` and the active personal
+/// subscription from `GET /api/subscriptions`. The last successful fetch is
+/// cached in `entitlements_cache.json` so limits still apply offline; without
+/// a usable cache the free plan applies, the same fallback the server uses.
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::account;
+use crate::api::{ApiClient, ApiError};
+use crate::config_store;
+use crate::error::{AppError, AppResult};
+use crate::migrations;
+use crate::time::now_unix;
+use crate::workspace::WorkspaceType;
+
+const CACHE_FILE: &str = migrations::ENTITLEMENTS_CACHE.file;
+/// A cached paid plan is trusted offline for this long after the last fetch.
+const OFFLINE_GRACE_SECS: i64 = 30 * 24 * 60 * 60;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+const UPGRADE_ROUTE: &str = "/billing/upgrade";
+
+// Server fallback when no plan row exists (`getActivePlanForOwner`).
+const FREE_PLAN_NAME: &str = "Basic";
+const FREE_MAX_STORAGE_MB: u64 = 500;
+const FREE_MAX_FILE_SIZE_MB: u64 = 5;
+const FREE_MAX_MEMBERS: u32 = 1;
+
+/// Row of `GET /api/plans`. The workspace limits are not columns yet: without
+/// one, personal workspaces are unlimited (the server has no such limit) and
+/// the team workspace limit follows from the price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanDefinition {
+    pub id: i64,
+    pub name: String,
+    #[serde(default, deserialize_with = "number_or_string")]
+    pub price: f64,
+    #[serde(default)]
+    pub plan_type: Option<String>,
+    #[serde(default)]
+    pub max_members: Option<u32>,
+    #[serde(default)]
+    pub max_storage_mb: Option<u64>,
+    #[serde(default)]
+    pub max_file_size_mb: Option<u64>,
+    #[serde(default)]
+    pub max_personal_workspaces: Option<u32>,
+    #[serde(default)]
+    pub max_team_workspaces: Option<u32>,
+}
+
+/// Active subscription as returned by `GET /api/subscriptions?active=true`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveSubscription {
+    pub id: i64,
+    pub plan_id: i64,
+    pub status: String,
+    #[serde(default)]
+    pub plan_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntitlementsCache {
+    #[serde(default)]
+    pub member_id: Option<String>,
+    #[serde(default)]
+    pub plans: Vec<PlanDefinition>,
+    #[serde(default)]
+    pub subscription: Option<ActiveSubscription>,
+    #[serde(default)]
+    pub fetched_at_unix: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntitlementSource {
+    Server,
+    Cache,
+    Default,
+}
+
+/// Limits in effect. `None` means unlimited.
+#[derive(Debug, Clone, Serialize)]
+pub struct Entitlements {
+    pub plan_id: Option<i64>,
+    pub plan_name: String,
+    pub paid: bool,
+    pub max_personal_workspaces: Option<u32>,
+    pub max_team_workspaces: Option<u32>,
+    pub max_members: Option<u32>,
+    pub max_storage_bytes: Option<u64>,
+    pub max_file_size_bytes: Option<u64>,
+    pub source: EntitlementSource,
+    pub fetched_at_unix: Option<i64>,
+}
+
+fn number_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    // mysql2 returns DECIMAL columns as strings.
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Number(n) => Ok(n.as_f64().unwrap_or(0.0)),
+        serde_json::Value::String(s) => s.trim().parse().map_err(serde::de::Error::custom),
+        _ => Ok(0.0),
+    }
+}
+
+impl Entitlements {
+    pub fn free() -> Self {
+        Entitlements {
+            plan_id: None,
+            plan_name: FREE_PLAN_NAME.to_string(),
+            paid: false,
+            max_personal_workspaces: None,
+            max_team_workspaces: Some(0),
+            max_members: Some(FREE_MAX_MEMBERS),
+            max_storage_bytes: Some(FREE_MAX_STORAGE_MB * BYTES_PER_MB),
+            max_file_size_bytes: Some(FREE_MAX_FILE_SIZE_MB * BYTES_PER_MB),
+            source: EntitlementSource::Default,
+            fetched_at_unix: None,
+        }
+    }
+
+    fn from_plan(plan: &PlanDefinition, source: EntitlementSource, fetched_at_unix: i64) -> Self {
+        let paid = plan.price > 0.0;
+        let free = Self::free();
+        Entitlements {
+            plan_id: Some(plan.id),
+            plan_name: plan.name.clone(),
+            paid,
+            max_personal_workspaces: plan.max_personal_workspaces,
+            max_team_workspaces: plan
+                .max_team_workspaces
+                .or(if paid { None } else { free.max_team_workspaces }),
+            max_members: plan.max_members.or(free.max_members),
+            max_storage_bytes: plan
+                .max_storage_mb
+                .map(|mb| mb * BYTES_PER_MB)
+                .or(free.max_storage_bytes),
+            // Never the free value: a paid plan would get the free file size.
+            max_file_size_bytes: plan.max_file_size_mb.map(|mb| mb * BYTES_PER_MB),
+            source,
+            fetched_at_unix: Some(fetched_at_unix),
+        }
+    }
+
+    /// The subscribed plan, or the cheapest personal plan like the server.
+    pub fn resolve(cache: &EntitlementsCache, source: EntitlementSource) -> Self {
+        let subscribed = cache
+            .subscription
+            .as_ref()
+            .filter(|s| s.status.eq_ignore_ascii_case("active"))
+            .and_then(|s| cache.plans.iter().find(|plan| plan.id == s.plan_id));
+        let fallback = || {
+            cache
+                .plans
+                .iter()
+                .filter(|plan| plan.plan_type.as_deref().unwrap_or("personal") == "personal")
+                .min_by(|a, b| a.price.total_cmp(&b.price))
+        };
+        match subscribed.or_else(fallback) {
+            Some(plan) => Self::from_plan(plan, source, cache.fetched_at_unix),
+            None => Self {
+                source,
+                fetched_at_unix: Some(cache.fetched_at_unix),
+                ..Self::free()
+            },
+        }
+    }
+
+    fn workspace_limit(&self, workspace_type: &WorkspaceType) -> Option<u32> {
+        match workspace_type {
+            WorkspaceType::Personal => self.max_personal_workspaces,
+            WorkspaceType::Team => self.max_team_workspaces,
+        }
+    }
+
+    /// Err with `upgrade_required` when one more workspace of this type would
+    /// exceed the plan. `current` is the number that exists now.
+    pub fn check_workspace_limit(&self, workspace_type: &WorkspaceType, current: usize) -> AppResult<()> {
+        let Some(limit) = self.workspace_limit(workspace_type) else {
+            return Ok(());
+        };
+        if current < limit as usize {
+            return Ok(());
+        }
+        let (feature, target) = match workspace_type {
+            WorkspaceType::Personal => ("personal_workspaces", "personal_workspace"),
+            WorkspaceType::Team => ("team_workspaces", "team_workspace"),
+        };
+        Err(upgrade_required(self, feature, target, limit, current))
+    }
+}
+
+fn upgrade_required(
+    entitlements: &Entitlements,
+    feature: &str,
+    target: &str,
+    limit: u32,
+    current: usize,
+) -> AppError {
+    AppError::entitlement(
+        "upgrade_required",
+        format!("The {} plan allows {} {}", entitlements.plan_name, limit, feature.replace('_', " ")),
+    )
+    .with_details(serde_json::json!({
+        "feature": feature,
+        "plan_id": entitlements.plan_id,
+        "plan_name": entitlements.plan_name,
+        "limit": limit,
+        "current": current,
+        "route": format!("{}?target={}", UPGRADE_ROUTE, target),
+    }))
+}
+
+fn cache_path(app: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    config_store::config_path(app, CACHE_FILE)
+}
+
+fn current_member_id(app: &tauri::AppHandle) -> Option<String> {
+    account::load_session(app)
+        .member_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+fn load_cache(app: &tauri::AppHandle) -> Option<EntitlementsCache> {
+    let path = cache_path(app).ok()?;
+    config_store::read::<EntitlementsCache>(&path, &migrations::ENTITLEMENTS_CACHE)
+}
+
+/// Limits from the cache, without touching the network. A cache written for
+/// another account, or older than the offline grace period, is ignored.
+pub fn current(app: &tauri::AppHandle) -> Entitlements {
+    let member_id = current_member_id(app);
+    match load_cache(app) {
+        Some(cache)
+            if cache.member_id.is_some()
+                && cache.member_id == member_id
+                && now_unix() - cache.fetched_at_unix <= OFFLINE_GRACE_SECS =>
+        {
+            Entitlements::resolve(&cache, EntitlementSource::Cache)
+        }
+        _ => Entitlements::free(),
+    }
+}
+
+pub async fn fetch(client: &ApiClient, member_id: &str) -> Result<EntitlementsCache, ApiError> {
+    let plans: Vec<PlanDefinition> = client.get("/api/plans").await?;
+    let subscription: Option<ActiveSubscription> = client
+        .get(&format!(
+            "/api/subscriptions?owner_id={}&owner_type=personal&active=true",
+            member_id
+        ))
+        .await?;
+    Ok(EntitlementsCache {
+        member_id: Some(member_id.to_string()),
+        plans,
+        subscription,
+        fetched_at_unix: now_unix(),
+    })
+}
+
+/// Remove the cache. Used on logout.
+pub fn clear_cache(app: &tauri::AppHandle) -> Result<(), String> {
+    config_store::remove(&cache_path(app)?)
+}
+
+#[tauri::command]
+pub fn get_entitlements(app: tauri::AppHandle) -> Entitlements {
+    current(&app)
+}
+
+/// Fetch the plan from the server and update the cache. When the server is
+/// unreachable the cached limits are returned instead.
+pub async fn refresh(app: &tauri::AppHandle) -> AppResult<Entitlements> {
+    let member_id = current_member_id(app)
+        .ok_or_else(|| AppError::from(ApiError::Unauthorized))?;
+    let client = ApiClient::from_app(app);
+    match fetch(&client, &member_id).await {
+        Ok(cache) => {
+            config_store::write_locked(app, &migrations::ENTITLEMENTS_CACHE, &cache)
+                .map_err(AppError::storage)?;
+            Ok(Entitlements::resolve(&cache, EntitlementSource::Server))
+        }
+        Err(ApiError::Offline(e)) => {
+            log::warn!("entitlements refresh failed, using cache: {}", e);
+            Ok(current(app))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// `refresh` without waiting, on startup and after sign-in. Skipped while
+/// signed out; until it completes the cached limits apply.
+pub fn refresh_in_background(app: &tauri::AppHandle) {
+    if current_member_id(app).is_none() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = refresh(&app).await {
+            log::warn!("entitlements: background refresh failed: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn refresh_entitlements(app: tauri::AppHandle) -> AppResult<Entitlements> {
+    refresh(&app).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCategory;
+    use serde_json::json;
+
+    fn cache(subscription: Option<(i64, &str)>) -> EntitlementsCache {
+        // Prices as mysql2 returns DECIMAL columns.
+        let plans = json!([
+            { "id": 4, "name": "Team Starter", "price": "0.00", "plan_type": "team", "max_members": 3 },
+            { "id": 2, "name": "Pro", "price": "9.99", "plan_type": "personal", "max_storage_mb": 10240, "max_file_size_mb": 100 },
+            { "id": 1, "name": "Basic", "price": "0.00", "plan_type": "personal", "max_storage_mb": 500, "max_file_size_mb": 5 },
+        ]);
+        EntitlementsCache {
+            member_id: Some("7".to_string()),
+            plans: serde_json::from_value(plans).unwrap(),
+            subscription: subscription.map(|(plan_id, status)| ActiveSubscription {
+                id: 1,
+                plan_id,
+                status: status.to_string(),
+                plan_name: None,
+            }),
+            fetched_at_unix: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn decimal_prices_are_parsed() {
+        let plans = cache(None).plans;
+        assert_eq!(plans[1].price, 9.99);
+        assert_eq!(plans[2].price, 0.0);
+        let numeric: PlanDefinition =
+            serde_json::from_value(json!({ "id": 1, "name": "Basic", "price": 0 })).unwrap();
+        assert_eq!(numeric.price, 0.0);
+    }
+
+    #[test]
+    fn subscribed_plan_wins() {
+        let resolved =
+            Entitlements::resolve(&cache(Some((2, "ACTIVE"))), EntitlementSource::Server);
+        assert_eq!(resolved.plan_id, Some(2));
+        assert!(resolved.paid);
+        assert_eq!(resolved.max_file_size_bytes, Some(100 * BYTES_PER_MB));
+        assert_eq!(resolved.source, EntitlementSource::Server);
+        assert_eq!(resolved.fetched_at_unix, Some(1_700_000_000));
+    }
+
+    #[test]
+    fn without_subscription_the_cheapest_personal_plan_applies() {
+        let resolved = Entitlements::resolve(&cache(None), EntitlementSource::Cache);
+        assert_eq!(resolved.plan_id, Some(1));
+        assert_eq!(resolved.plan_name, "Basic");
+        assert!(!resolved.paid);
+        assert_eq!(resolved.max_team_workspaces, Some(0));
+        assert_eq!(resolved.max_storage_bytes, Some(500 * BYTES_PER_MB));
+    }
+
+    #[test]
+    fn inactive_subscription_falls_back() {
+        for status in ["CANCELED", "EXPIRED", "pending"] {
+            let resolved =
+                Entitlements::resolve(&cache(Some((2, status))), EntitlementSource::Cache);
+            assert_eq!(resolved.plan_id, Some(1), "{}", status);
+        }
+        // A subscription to a plan that is no longer listed also falls back.
+        let resolved =
+            Entitlements::resolve(&cache(Some((99, "ACTIVE"))), EntitlementSource::Cache);
+        assert_eq!(resolved.plan_id, Some(1));
+    }
+
+    #[test]
+    fn without_plans_the_free_limits_apply() {
+        let mut empty = cache(None);
+        empty.plans.clear();
+        let resolved = Entitlements::resolve(&empty, EntitlementSource::Cache);
+        assert_eq!(resolved.plan_id, None);
+        assert_eq!(resolved.plan_name, FREE_PLAN_NAME);
+        assert_eq!(resolved.source, EntitlementSource::Cache);
+        assert_eq!(resolved.max_team_workspaces, Some(0));
+    }
+
+    #[test]
+    fn paid_plan_is_unlimited() {
+        let resolved =
+            Entitlements::resolve(&cache(Some((2, "ACTIVE"))), EntitlementSource::Server);
+        assert_eq!(resolved.max_personal_workspaces, None);
+        assert_eq!(resolved.max_team_workspaces, None);
+        for workspace_type in [WorkspaceType::Personal, WorkspaceType::Team] {
+            assert!(resolved
+                .check_workspace_limit(&workspace_type, 1_000)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn personal_workspaces_are_unlimited_on_the_free_plan() {
+        let resolved = Entitlements::resolve(&cache(None), EntitlementSource::Cache);
+        assert!(resolved
+            .check_workspace_limit(&WorkspaceType::Personal, 50)
+            .is_ok());
+        assert!(Entitlements::free()
+            .check_workspace_limit(&WorkspaceType::Personal, 50)
+            .is_ok());
+    }
+
+    #[test]
+    fn exceeding_a_limit_requires_an_upgrade() {
+        let mut limited = cache(None);
+        limited.plans[2].max_personal_workspaces = Some(2);
+        let resolved = Entitlements::resolve(&limited, EntitlementSource::Cache);
+        assert!(resolved
+            .check_workspace_limit(&WorkspaceType::Personal, 1)
+            .is_ok());
+
+        let err = resolved
+            .check_workspace_limit(&WorkspaceType::Personal, 2)
+            .unwrap_err();
+        assert_eq!(err.code, "upgrade_required");
+        assert_eq!(err.category, ErrorCategory::Entitlement);
+        assert_eq!(
+            err.details,
+            json!({
+                "feature": "personal_workspaces",
+                "plan_id": 1,
+                "plan_name": "Basic",
+                "limit": 2,
+                "current": 2,
+                "route": "/billing/upgrade?target=personal_workspace",
+            })
+        );
+
+        let err = resolved
+            .check_workspace_limit(&WorkspaceType::Team, 0)
+            .unwrap_err();
+        assert_eq!(err.details["feature"], "team_workspaces");
+        assert_eq!(err.details["limit"], 0);
+        assert_eq!(
+            err.details["route"],
+            "/billing/upgrade?target=team_workspace"
+        );
+    }
+}
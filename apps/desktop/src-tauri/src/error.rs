@@ -0,0 +1,159 @@
+/// Error type returned by Tauri commands.
+///
+/// Serialized as `{ code, category, message, details }`. `code` is stable and
+/// meant for the UI to localize or branch on; `message` is an English fallback
+/// for logs and should not be string-matched.
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::ApiError;
+use crate::config_store::LockError;
+use crate::nickname::NicknameError;
+use crate::preferences::PreferencesValidationError;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Validation,
+    NotFound,
+    Io,
+    Conflict,
+    Auth,
+    /// The current plan does not allow the action; `details` says what to upgrade.
+    Entitlement,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppError {
+    pub code: String,
+    pub category: ErrorCategory,
+    pub message: String,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub details: Value,
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn new(category: ErrorCategory, code: &str, message: impl Into<String>) -> Self {
+        AppError {
+            code: code.to_string(),
+            category,
+            message: message.into(),
+            details: Value::Null,
+        }
+    }
+
+    pub fn validation(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::Validation, code, message)
+    }
+
+    pub fn not_found(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::NotFound, code, message)
+    }
+
+    pub fn io(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::Io, code, message)
+    }
+
+    pub fn conflict(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::Conflict, code, message)
+    }
+
+    pub fn auth(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::Auth, code, message)
+    }
+
+    pub fn entitlement(code: &str, message: impl Into<String>) -> Self {
+        Self::new(ErrorCategory::Entitlement, code, message)
+    }
+
+    /// Reading or writing a file in the config directory failed.
+    pub fn storage(message: impl Into<String>) -> Self {
+        Self::io("storage_failed", message)
+    }
+
+    /// A managed `Mutex` was poisoned by a panic on another thread.
+    pub fn state_lock(state: &str) -> Self {
+        Self::io("state_lock_failed", format!("Failed to lock {} state", state))
+            .with_details(serde_json::json!({ "state": state }))
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl From<NicknameError> for AppError {
+    fn from(e: NicknameError) -> Self {
+        let category = match e {
+            NicknameError::Reserved { .. } => ErrorCategory::Conflict,
+            _ => ErrorCategory::Validation,
+        };
+        // The nickname error is already tagged with `code`; the remaining
+        // fields become the details.
+        let mut details = serde_json::to_value(&e).unwrap_or(Value::Null);
+        let code = details
+            .as_object_mut()
+            .and_then(|fields| fields.remove("code"))
+            .and_then(|code| code.as_str().map(|c| format!("nickname_{}", c)))
+            .unwrap_or_else(|| "nickname_invalid".to_string());
+        if details.as_object().is_some_and(|fields| fields.is_empty()) {
+            details = Value::Null;
+        }
+        AppError {
+            code,
+            category,
+            message: e.to_string(),
+            details,
+        }
+    }
+}
+
+impl From<PreferencesValidationError> for AppError {
+    fn from(e: PreferencesValidationError) -> Self {
+        let category = if e.code == "storage_error" {
+            ErrorCategory::Io
+        } else {
+            ErrorCategory::Validation
+        };
+        let message = e
+            .fields
+            .first()
+            .map(|field| field.message.clone())
+            .unwrap_or_else(|| "Invalid preferences".to_string());
+        AppError::new(category, &e.code, message)
+            .with_details(serde_json::json!({ "fields": e.fields }))
+    }
+}
+
+impl From<LockError> for AppError {
+    fn from(e: LockError) -> Self {
+        let message = e.to_string();
+        match e {
+            LockError::Timeout { file, waited_ms } => AppError::conflict("config_locked", message)
+                .with_details(serde_json::json!({ "file": file, "waited_ms": waited_ms })),
+            LockError::Io(_) => AppError::storage(message),
+        }
+    }
+}
+
+impl From<ApiError> for AppError {
+    fn from(e: ApiError) -> Self {
+        let message = e.to_string();
+        match e {
+            ApiError::Offline(_) => AppError::io("server_unreachable", message),
+            ApiError::Unauthorized => AppError::auth("unauthorized", message),
+            ApiError::Status(status) => AppError::io("server_error", message)
+                .with_details(serde_json::json!({ "status": status })),
+            ApiError::Decode(_) => AppError::io("invalid_server_response", message),
+        }
+    }
+}
@@ -0,0 +1,654 @@
+/// Korean public holidays (관공서의 공휴일) and the Korean lunar calendar.
+///
+/// The lunar calendar is computed astronomically rather than read from a
+/// table: new moons (Meeus ch. 49) and principal solar terms (중기) are
+/// evaluated in KST (UTC+9), month 11 always contains the winter solstice, and
+/// in a 13-month year the first month without a principal term is the leap
+/// month. This reproduces KASI's calendar, including the years where Korea's
+/// dates differ from the Chinese calendar (e.g. 설날 2027-02-07).
+///
+/// Holidays follow current law, including 대체공휴일:
+/// - 설날·추석 (2014~): a Sunday or another holiday inside the three days
+/// - 어린이날 (2014~): Saturday, Sunday or another holiday
+/// - 삼일절·광복절·개천절·한글날 (2021-08-04~), 부처님오신날·기독탄신일
+///   (2023-05-04~): Saturday, Sunday or another holiday
+///
+/// Election days and one-off temporary holidays are not included.
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::f64::consts::PI;
+use std::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+
+/// Range accepted by the commands. The engine itself works outside it, but
+/// ΔT estimates and Korean standard time history make results unreliable.
+const MIN_YEAR: i32 = 1900;
+const MAX_YEAR: i32 = 2100;
+const KST_OFFSET_DAYS: f64 = 9.0 / 24.0;
+const SYNODIC_MONTH: f64 = 29.530588861;
+/// JDN of 0001-01-01 (proleptic Gregorian) minus one, for chrono day counts.
+const JDN_CE_OFFSET: i64 = 1_721_425;
+
+static HOLIDAY_CACHE: Mutex<Option<HashMap<i32, Vec<Holiday>>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Holiday {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    pub name: String,
+    /// Name of the holiday this day substitutes for (대체공휴일).
+    pub substitute_for: Option<String>,
+    #[serde(skip)]
+    pub day: NaiveDate,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LunarDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub is_leap_month: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LunarMonth {
+    month: u32,
+    leap: bool,
+    start: NaiveDate,
+    days: u32,
+}
+
+// ── Astronomy ──────────────────────────────────────────────────────────
+
+fn sin_deg(deg: f64) -> f64 {
+    (deg * PI / 180.0).sin()
+}
+
+/// ΔT = TT − UT in seconds (Espenak & Meeus polynomials).
+fn delta_t_seconds(year: f64) -> f64 {
+    let u = (year - 1820.0) / 100.0;
+    match year {
+        y if y < 1900.0 => -20.0 + 32.0 * u * u,
+        y if y < 1920.0 => {
+            let t = y - 1900.0;
+            -2.79 + 1.494119 * t - 0.0598939 * t.powi(2) + 0.0061966 * t.powi(3)
+                - 0.000197 * t.powi(4)
+        }
+        y if y < 1941.0 => {
+            let t = y - 1920.0;
+            21.20 + 0.84493 * t - 0.076100 * t.powi(2) + 0.0020936 * t.powi(3)
+        }
+        y if y < 1961.0 => {
+            let t = y - 1950.0;
+            29.07 + 0.407 * t - t.powi(2) / 233.0 + t.powi(3) / 2547.0
+        }
+        y if y < 1986.0 => {
+            let t = y - 1975.0;
+            45.45 + 1.067 * t - t.powi(2) / 260.0 - t.powi(3) / 718.0
+        }
+        y if y < 2005.0 => {
+            let t = y - 2000.0;
+            63.86 + 0.3345 * t - 0.060374 * t.powi(2) + 0.0017275 * t.powi(3)
+                + 0.000651814 * t.powi(4)
+                + 0.00002373599 * t.powi(5)
+        }
+        y if y < 2050.0 => {
+            let t = y - 2000.0;
+            62.92 + 0.32217 * t + 0.005589 * t.powi(2)
+        }
+        y if y < 2150.0 => -20.0 + 32.0 * u * u - 0.5628 * (2150.0 - y),
+        _ => -20.0 + 32.0 * u * u,
+    }
+}
+
+fn jde_to_jd_ut(jde: f64) -> f64 {
+    let year = 2000.0 + (jde - 2451545.0) / 365.25;
+    jde - delta_t_seconds(year) / 86400.0
+}
+
+fn jd_ut_to_jde(jd: f64) -> f64 {
+    let year = 2000.0 + (jd - 2451545.0) / 365.25;
+    jd + delta_t_seconds(year) / 86400.0
+}
+
+fn kst_date(jd_ut: f64) -> NaiveDate {
+    let jdn = (jd_ut + 0.5 + KST_OFFSET_DAYS).floor() as i64;
+    NaiveDate::from_num_days_from_ce_opt((jdn - JDN_CE_OFFSET) as i32).unwrap_or(NaiveDate::MIN)
+}
+
+/// JD (UT) of KST midnight at the start of `date`.
+fn kst_midnight_jd(date: NaiveDate) -> f64 {
+    (date.num_days_from_ce() as i64 + JDN_CE_OFFSET) as f64 - 0.5 - KST_OFFSET_DAYS
+}
+
+/// Instant (JDE) of the `k`-th new moon counted from 2000-01-06.
+fn new_moon_jde(k: f64) -> f64 {
+    let t = k / 1236.85;
+    let jde = 2451550.09766 + SYNODIC_MONTH * k + 0.00015437 * t.powi(2)
+        - 0.000000150 * t.powi(3)
+        + 0.00000000073 * t.powi(4);
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t.powi(2);
+    let m = 2.5534 + 29.10535670 * k - 0.0000014 * t.powi(2) - 0.00000011 * t.powi(3);
+    let mp = 201.5643 + 385.81693528 * k + 0.0107582 * t.powi(2) + 0.00001238 * t.powi(3)
+        - 0.000000058 * t.powi(4);
+    let f = 160.7108 + 390.67050284 * k - 0.0016118 * t.powi(2) - 0.00000227 * t.powi(3)
+        + 0.000000011 * t.powi(4);
+    let om = 124.7746 - 1.56375588 * k + 0.0020672 * t.powi(2) + 0.00000215 * t.powi(3);
+
+    let correction = -0.40720 * sin_deg(mp)
+        + 0.17241 * e * sin_deg(m)
+        + 0.01608 * sin_deg(2.0 * mp)
+        + 0.01039 * sin_deg(2.0 * f)
+        + 0.00739 * e * sin_deg(mp - m)
+        - 0.00514 * e * sin_deg(mp + m)
+        + 0.00208 * e * e * sin_deg(2.0 * m)
+        - 0.00111 * sin_deg(mp - 2.0 * f)
+        - 0.00057 * sin_deg(mp + 2.0 * f)
+        + 0.00056 * e * sin_deg(2.0 * mp + m)
+        - 0.00042 * sin_deg(3.0 * mp)
+        + 0.00042 * e * sin_deg(m + 2.0 * f)
+        + 0.00038 * e * sin_deg(m - 2.0 * f)
+        - 0.00024 * e * sin_deg(2.0 * mp - m)
+        - 0.00017 * sin_deg(om)
+        - 0.00007 * sin_deg(mp + 2.0 * m)
+        + 0.00004 * sin_deg(2.0 * mp - 2.0 * f)
+        + 0.00004 * sin_deg(3.0 * m)
+        + 0.00003 * sin_deg(mp + m - 2.0 * f)
+        + 0.00003 * sin_deg(2.0 * mp + 2.0 * f)
+        - 0.00003 * sin_deg(mp + m + 2.0 * f)
+        + 0.00003 * sin_deg(mp - m + 2.0 * f)
+        - 0.00002 * sin_deg(mp - m - 2.0 * f)
+        - 0.00002 * sin_deg(3.0 * mp + m)
+        + 0.00002 * sin_deg(4.0 * mp);
+
+    let planetary = [
+        (0.000325, 299.77 + 0.107408 * k - 0.009173 * t.powi(2)),
+        (0.000165, 251.88 + 0.016321 * k),
+        (0.000164, 251.83 + 26.651886 * k),
+        (0.000126, 349.42 + 36.412478 * k),
+        (0.000110, 84.66 + 18.206239 * k),
+        (0.000062, 141.74 + 53.303771 * k),
+        (0.000060, 207.14 + 2.453732 * k),
+        (0.000056, 154.84 + 7.306860 * k),
+        (0.000047, 34.52 + 27.261239 * k),
+        (0.000042, 207.19 + 0.121824 * k),
+        (0.000040, 291.34 + 1.844379 * k),
+        (0.000037, 161.72 + 24.198154 * k),
+        (0.000035, 239.56 + 25.513099 * k),
+        (0.000023, 331.55 + 3.592518 * k),
+    ]
+    .iter()
+    .map(|(coefficient, angle)| coefficient * sin_deg(*angle))
+    .sum::<f64>();
+
+    jde + correction + planetary
+}
+
+/// KST date of the `k`-th new moon.
+fn new_moon_date(k: i64) -> NaiveDate {
+    kst_date(jde_to_jd_ut(new_moon_jde(k as f64)))
+}
+
+/// Apparent geocentric longitude of the sun in degrees (Meeus ch. 25).
+fn sun_longitude(jde: f64) -> f64 {
+    let t = (jde - 2451545.0) / 36525.0;
+    let l0 = 280.46646 + 36000.76983 * t + 0.0003032 * t * t;
+    let m = 357.52911 + 35999.05029 * t - 0.0001537 * t * t;
+    let c = (1.914602 - 0.004817 * t - 0.000014 * t * t) * sin_deg(m)
+        + (0.019993 - 0.000101 * t) * sin_deg(2.0 * m)
+        + 0.000289 * sin_deg(3.0 * m);
+    let omega = 125.04 - 1934.136 * t;
+    (l0 + c - 0.00569 - 0.00478 * sin_deg(omega)).rem_euclid(360.0)
+}
+
+/// Sun longitude at KST midnight starting `date`.
+fn sun_longitude_on(date: NaiveDate) -> f64 {
+    sun_longitude(jd_ut_to_jde(kst_midnight_jd(date)))
+}
+
+/// KST date of the winter solstice (sun longitude 270°) in `year`.
+fn winter_solstice_date(year: i32) -> NaiveDate {
+    let approx = NaiveDate::from_ymd_opt(year, 12, 21).unwrap_or(NaiveDate::MIN);
+    let mut jde = jd_ut_to_jde(kst_midnight_jd(approx));
+    for _ in 0..20 {
+        let diff = (270.0 - sun_longitude(jde) + 540.0).rem_euclid(360.0) - 180.0;
+        jde += diff / 0.98564736;
+        if diff.abs() < 1e-7 {
+            break;
+        }
+    }
+    kst_date(jde_to_jd_ut(jde))
+}
+
+/// Index of the new moon starting month 11 (동짓달) of `year`.
+fn month_eleven_k(year: i32) -> i64 {
+    let solstice = winter_solstice_date(year);
+    let mut k = ((kst_midnight_jd(solstice) - 2451550.09766) / SYNODIC_MONTH).floor() as i64;
+    while new_moon_date(k) > solstice {
+        k -= 1;
+    }
+    while new_moon_date(k + 1) <= solstice {
+        k += 1;
+    }
+    k
+}
+
+// ── Lunar calendar ─────────────────────────────────────────────────────
+
+/// Months from 동짓달 of `year - 1` up to, not including, 동짓달 of `year`.
+/// Months 11 and 12 at the front belong to lunar year `year - 1`.
+fn lunar_months(year: i32) -> Vec<LunarMonth> {
+    let first = month_eleven_k(year - 1);
+    let last = month_eleven_k(year);
+    let starts: Vec<NaiveDate> = (first..=last).map(new_moon_date).collect();
+    let count = starts.len() - 1;
+
+    // A month without a principal term: the sun stays within one 30° sign.
+    let has_principal_term = |i: usize| {
+        let sign = |date| (sun_longitude_on(date) / 30.0).floor() as i64;
+        sign(starts[i]) != sign(starts[i + 1])
+    };
+    let leap_index = (count == 13)
+        .then(|| (1..count).find(|&i| !has_principal_term(i)))
+        .flatten();
+
+    let mut months = Vec::with_capacity(count);
+    let mut number = 11;
+    for i in 0..count {
+        let leap = Some(i) == leap_index;
+        if i > 0 && !leap {
+            number = number % 12 + 1;
+        }
+        months.push(LunarMonth {
+            month: number,
+            leap,
+            start: starts[i],
+            days: (starts[i + 1] - starts[i]).num_days() as u32,
+        });
+    }
+    months
+}
+
+/// Lunar year of each month in `lunar_months(year)`.
+fn lunar_year_of(year: i32, months: &[LunarMonth], index: usize) -> i32 {
+    let before_new_year = months[..=index].iter().all(|m| m.month >= 11);
+    if before_new_year {
+        year - 1
+    } else {
+        year
+    }
+}
+
+pub fn lunar_to_solar(year: i32, month: u32, day: u32, is_leap_month: bool) -> Option<NaiveDate> {
+    // 동짓달 and 섣달 of lunar year Y sit at the front of the next list.
+    let list_year = if month >= 11 { year + 1 } else { year };
+    let months = lunar_months(list_year);
+    months
+        .iter()
+        .enumerate()
+        .find(|(i, m)| {
+            m.month == month && m.leap == is_leap_month && lunar_year_of(list_year, &months, *i) == year
+        })
+        .filter(|(_, m)| day >= 1 && day <= m.days)
+        .map(|(_, m)| m.start + Duration::days(day as i64 - 1))
+}
+
+pub fn solar_to_lunar(date: NaiveDate) -> Option<LunarDate> {
+    for list_year in [date.year(), date.year() + 1] {
+        let months = lunar_months(list_year);
+        for (i, m) in months.iter().enumerate() {
+            let offset = (date - m.start).num_days();
+            if offset >= 0 && offset < m.days as i64 {
+                return Some(LunarDate {
+                    year: lunar_year_of(list_year, &months, i),
+                    month: m.month,
+                    day: offset as u32 + 1,
+                    is_leap_month: m.leap,
+                });
+            }
+        }
+    }
+    None
+}
+
+// ── Holidays ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SubstituteRule {
+    None,
+    /// 설날·추석: Sunday or overlap.
+    SundayOrOverlap,
+    WeekendOrOverlap,
+}
+
+struct Observance {
+    name: &'static str,
+    days: Vec<NaiveDate>,
+    rule: SubstituteRule,
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap_or(NaiveDate::MIN)
+}
+
+fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn observances(year: i32) -> Vec<Observance> {
+    let rule_from = |since: NaiveDate, day: NaiveDate, rule| {
+        if day >= since {
+            rule
+        } else {
+            SubstituteRule::None
+        }
+    };
+    let fixed = |name, month, day, since: Option<NaiveDate>| {
+        let date = ymd(year, month, day);
+        Observance {
+            name,
+            days: vec![date],
+            rule: since
+                .map(|since| rule_from(since, date, SubstituteRule::WeekendOrOverlap))
+                .unwrap_or(SubstituteRule::None),
+        }
+    };
+    let three_days = |name, center: NaiveDate| Observance {
+        name,
+        days: vec![center - Duration::days(1), center, center + Duration::days(1)],
+        rule: rule_from(ymd(2014, 1, 1), center, SubstituteRule::SundayOrOverlap),
+    };
+    let from_2021 = Some(ymd(2021, 8, 4));
+
+    let mut list = vec![
+        fixed("신정", 1, 1, None),
+        fixed("삼일절", 3, 1, from_2021),
+        fixed("어린이날", 5, 5, Some(ymd(2014, 1, 1))),
+        fixed("현충일", 6, 6, None),
+        fixed("광복절", 8, 15, from_2021),
+        fixed("개천절", 10, 3, from_2021),
+        fixed("기독탄신일", 12, 25, Some(ymd(2023, 5, 4))),
+    ];
+    // 한글날 was not a public holiday from 1991 to 2012.
+    if !(1991..=2012).contains(&year) {
+        list.push(fixed("한글날", 10, 9, from_2021));
+    }
+    if let Some(seollal) = lunar_to_solar(year, 1, 1, false) {
+        list.push(three_days("설날", seollal));
+    }
+    if let Some(chuseok) = lunar_to_solar(year, 8, 15, false) {
+        list.push(three_days("추석", chuseok));
+    }
+    if let Some(buddha) = lunar_to_solar(year, 4, 8, false) {
+        list.push(Observance {
+            name: "부처님오신날",
+            days: vec![buddha],
+            rule: rule_from(ymd(2023, 5, 4), buddha, SubstituteRule::WeekendOrOverlap),
+        });
+    }
+    list.sort_by_key(|o| o.days[0]);
+    list
+}
+
+fn compute_holidays(year: i32) -> Vec<Holiday> {
+    let observances = observances(year);
+    let mut per_day: HashMap<NaiveDate, usize> = HashMap::new();
+    for day in observances.iter().flat_map(|o| o.days.iter()) {
+        *per_day.entry(*day).or_default() += 1;
+    }
+    let mut taken: BTreeSet<NaiveDate> = per_day.keys().copied().collect();
+    // An overlap is compensated once, by whichever holiday sees it first.
+    let mut compensated: HashSet<NaiveDate> = HashSet::new();
+
+    let mut holidays = Vec::new();
+    for observance in &observances {
+        for day in &observance.days {
+            holidays.push(holiday(*day, observance.name, None));
+        }
+
+        let triggers = observance
+            .days
+            .iter()
+            .filter(|day| {
+                let weekend = match observance.rule {
+                    SubstituteRule::None => return false,
+                    SubstituteRule::SundayOrOverlap => day.weekday() == Weekday::Sun,
+                    SubstituteRule::WeekendOrOverlap => is_weekend(**day),
+                };
+                let overlap = per_day.get(*day).copied().unwrap_or(0) > 1 && compensated.insert(**day);
+                weekend || overlap
+            })
+            .count();
+
+        let mut candidate = *observance.days.last().unwrap_or(&NaiveDate::MIN);
+        for _ in 0..triggers {
+            loop {
+                candidate += Duration::days(1);
+                if !is_weekend(candidate) && !taken.contains(&candidate) {
+                    break;
+                }
+            }
+            taken.insert(candidate);
+            holidays.push(holiday(candidate, "대체공휴일", Some(observance.name)));
+        }
+    }
+
+    holidays.retain(|h| h.day.year() == year);
+    holidays.sort_by_key(|h| h.day);
+    holidays
+}
+
+fn holiday(day: NaiveDate, name: &str, substitute_for: Option<&str>) -> Holiday {
+    Holiday {
+        date: day.format("%Y-%m-%d").to_string(),
+        name: name.to_string(),
+        substitute_for: substitute_for.map(str::to_string),
+        day,
+    }
+}
+
+/// Public holidays of `year`, including substitute holidays. Cached.
+pub fn holidays_for_year(year: i32) -> Vec<Holiday> {
+    let mut cache = match HOLIDAY_CACHE.lock() {
+        Ok(cache) => cache,
+        Err(_) => return compute_holidays(year),
+    };
+    cache
+        .get_or_insert_with(HashMap::new)
+        .entry(year)
+        .or_insert_with(|| compute_holidays(year))
+        .clone()
+}
+
+pub fn is_holiday(date: NaiveDate) -> bool {
+    holidays_for_year(date.year()).iter().any(|h| h.day == date)
+}
+
+// ── Commands ───────────────────────────────────────────────────────────
+
+fn check_year(year: i32) -> AppResult<()> {
+    if (MIN_YEAR..=MAX_YEAR).contains(&year) {
+        Ok(())
+    } else {
+        Err(AppError::validation(
+            "year_out_of_range",
+            format!("Year must be between {} and {}", MIN_YEAR, MAX_YEAR),
+        ))
+    }
+}
+
+fn parse_date(value: &str) -> AppResult<NaiveDate> {
+    let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+        AppError::validation("invalid_date", "Date must be in YYYY-MM-DD format")
+    })?;
+    check_year(date.year())?;
+    Ok(date)
+}
+
+#[tauri::command]
+pub fn get_holidays(year: i32) -> AppResult<Vec<Holiday>> {
+    check_year(year)?;
+    Ok(holidays_for_year(year))
+}
+
+#[tauri::command]
+pub fn convert_solar_to_lunar(date: String) -> AppResult<LunarDate> {
+    let date = parse_date(&date)?;
+    solar_to_lunar(date).ok_or_else(|| {
+        AppError::validation("invalid_date", "Date is outside the supported lunar range")
+    })
+}
+
+/// Returns the solar date as `YYYY-MM-DD`.
+#[tauri::command]
+pub fn convert_lunar_to_solar(
+    year: i32,
+    month: u32,
+    day: u32,
+    is_leap_month: Option<bool>,
+) -> AppResult<String> {
+    check_year(year)?;
+    lunar_to_solar(year, month, day, is_leap_month.unwrap_or(false))
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .ok_or_else(|| {
+            AppError::validation(
+                "invalid_lunar_date",
+                "Lunar date does not exist in that year",
+            )
+            .with_details(serde_json::json!({
+                "year": year,
+                "month": month,
+                "day": day,
+                "is_leap_month": is_leap_month.unwrap_or(false),
+            }))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn substitutes(year: i32) -> Vec<(String, String)> {
+        holidays_for_year(year)
+            .into_iter()
+            .filter_map(|h| h.substitute_for.map(|of| (h.date, of)))
+            .collect()
+    }
+
+    #[test]
+    fn seollal_and_chuseok_match_kasi() {
+        let cases = [
+            ((2024, 1, 1), "2024-02-10"),
+            ((2025, 1, 1), "2025-01-29"),
+            ((2026, 1, 1), "2026-02-17"),
+            // Korea and China differ this year (China: 2027-02-06).
+            ((2027, 1, 1), "2027-02-07"),
+            ((2024, 8, 15), "2024-09-17"),
+            ((2025, 8, 15), "2025-10-06"),
+            ((2028, 8, 15), "2028-10-03"),
+            ((2033, 8, 15), "2033-09-08"),
+        ];
+        for ((year, month, day), expected) in cases {
+            assert_eq!(
+                lunar_to_solar(year, month, day, false),
+                Some(date(expected)),
+                "lunar {}-{}-{}",
+                year,
+                month,
+                day
+            );
+        }
+    }
+
+    #[test]
+    fn leap_months_match_kasi() {
+        assert_eq!(lunar_to_solar(2023, 2, 1, true), Some(date("2023-03-22")));
+        assert_eq!(lunar_to_solar(2025, 6, 1, true), Some(date("2025-07-25")));
+        assert_eq!(lunar_to_solar(2024, 6, 1, true), None);
+        assert_eq!(
+            solar_to_lunar(date("2025-07-25")),
+            Some(LunarDate {
+                year: 2025,
+                month: 6,
+                day: 1,
+                is_leap_month: true,
+            })
+        );
+    }
+
+    #[test]
+    fn solar_to_lunar_round_trips() {
+        let mut day = date("2024-01-01");
+        while day < date("2027-01-01") {
+            let lunar = solar_to_lunar(day).unwrap();
+            assert_eq!(
+                lunar_to_solar(lunar.year, lunar.month, lunar.day, lunar.is_leap_month),
+                Some(day)
+            );
+            day += Duration::days(1);
+        }
+    }
+
+    #[test]
+    fn lunar_year_spans_new_year() {
+        assert_eq!(
+            solar_to_lunar(date("2025-01-28")),
+            Some(LunarDate {
+                year: 2024,
+                month: 12,
+                day: 29,
+                is_leap_month: false,
+            })
+        );
+    }
+
+    #[test]
+    fn substitute_holidays_match_published_dates() {
+        assert_eq!(
+            substitutes(2023),
+            vec![
+                ("2023-01-24".to_string(), "설날".to_string()),
+                ("2023-05-29".to_string(), "부처님오신날".to_string()),
+            ]
+        );
+        assert_eq!(
+            substitutes(2024),
+            vec![
+                ("2024-02-12".to_string(), "설날".to_string()),
+                ("2024-05-06".to_string(), "어린이날".to_string()),
+            ]
+        );
+        // 어린이날 and 부처님오신날 fall on the same day.
+        assert_eq!(substitutes(2025)[0].0, "2025-03-03");
+        assert!(substitutes(2025).iter().any(|(day, _)| day == "2025-05-06"));
+        // 추석 Sunday; 10-09 한글날 is already taken.
+        assert!(substitutes(2025)
+            .iter()
+            .any(|(day, of)| day == "2025-10-08" && of == "추석"));
+        assert_eq!(
+            substitutes(2026),
+            vec![
+                ("2026-03-02".to_string(), "삼일절".to_string()),
+                ("2026-05-25".to_string(), "부처님오신날".to_string()),
+                ("2026-08-17".to_string(), "광복절".to_string()),
+                ("2026-10-05".to_string(), "개천절".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rules_apply_only_from_their_start_date() {
+        // 광복절 2020 was a Saturday, before the 2021 extension.
+        assert!(!is_holiday(date("2020-08-17")));
+        // 기독탄신일 2022 was a Sunday, before the 2023 extension.
+        assert!(!is_holiday(date("2022-12-26")));
+        assert!(!holidays_for_year(2000).iter().any(|h| h.name == "한글날"));
+        assert!(is_holiday(date("2013-10-09")));
+    }
+}
@@ -1,9 +1,26 @@
 mod account;
 mod alarm;
+mod api;
+mod backup;
+mod config_store;
+mod data_location;
 mod desktop_attach;
+mod entitlements;
+mod error;
+mod holidays;
+mod migrations;
+mod nickname;
 mod oauth;
 mod position;
+mod preferences;
+mod settings_bundle;
+mod team_cache;
+mod time;
 mod workspace;
+mod workspace_session;
+mod workspace_storage;
+mod workspace_sync;
+mod workspace_template;
 
 #[cfg(target_os = "windows")]
 mod autostart;
@@ -32,9 +49,10 @@ pub fn run() {
             account::update_nickname,
             account::upload_profile_image,
             account::delete_profile_image,
-            account::get_user_preferences,
-            account::save_user_preferences,
+            preferences::get_user_preferences,
+            preferences::save_user_preferences,
             account::get_auth_session,
+            account::get_session_state,
             account::save_auth_session,
             account::clear_auth_session,
             account::logout,
@@ -44,6 +62,18 @@ pub fn run() {
             alarm::sync_task_alarms,
             alarm::snooze_alarm,
             alarm::dismiss_alarm,
+            config_store::get_config_load_issues,
+            settings_bundle::export_settings,
+            settings_bundle::preview_settings_import,
+            settings_bundle::apply_settings_import,
+            backup::list_backups,
+            backup::create_backup,
+            backup::restore_backup,
+            data_location::get_data_location,
+            data_location::move_data_directory,
+            holidays::get_holidays,
+            holidays::convert_solar_to_lunar,
+            holidays::convert_lunar_to_solar,
             workspace::get_workspace_state,
             workspace::list_personal_workspaces,
             workspace::list_team_workspaces,
@@ -52,8 +82,29 @@ pub fn run() {
             workspace::create_workspace,
             workspace::rename_workspace,
             workspace::delete_workspace,
+            workspace::reorder_workspaces,
+            workspace::set_workspace_pinned,
+            workspace::update_workspace_appearance,
+            workspace::archive_workspace,
+            workspace::unarchive_workspace,
+            workspace::restore_workspace,
+            workspace::list_workspace_trash,
+            workspace::purge_workspace,
             workspace::get_team_workspace_creation_path,
             workspace::create_team_workspace,
+            workspace_sync::sync_workspaces,
+            workspace_sync::resolve_workspace_conflict,
+            entitlements::get_entitlements,
+            entitlements::refresh_entitlements,
+            workspace_session::get_workspace_session,
+            workspace_session::save_workspace_session,
+            team_cache::get_team_access,
+            team_cache::has_permission,
+            workspace_template::list_workspace_templates,
+            workspace_template::capture_workspace_template,
+            workspace_template::delete_workspace_template,
+            workspace_storage::get_workspace_data_dirs,
+            workspace_storage::clear_workspace_cache,
             desktop_attach::toggle_desktop_mode,
             desktop_attach::is_desktop_mode,
             desktop_attach::set_desktop_mode,
@@ -75,9 +126,10 @@ pub fn run() {
             account::update_nickname,
             account::upload_profile_image,
             account::delete_profile_image,
-            account::get_user_preferences,
-            account::save_user_preferences,
+            preferences::get_user_preferences,
+            preferences::save_user_preferences,
             account::get_auth_session,
+            account::get_session_state,
             account::save_auth_session,
             account::clear_auth_session,
             account::logout,
@@ -87,6 +139,18 @@ pub fn run() {
             alarm::sync_task_alarms,
             alarm::snooze_alarm,
             alarm::dismiss_alarm,
+            config_store::get_config_load_issues,
+            settings_bundle::export_settings,
+            settings_bundle::preview_settings_import,
+            settings_bundle::apply_settings_import,
+            backup::list_backups,
+            backup::create_backup,
+            backup::restore_backup,
+            data_location::get_data_location,
+            data_location::move_data_directory,
+            holidays::get_holidays,
+            holidays::convert_solar_to_lunar,
+            holidays::convert_lunar_to_solar,
             workspace::get_workspace_state,
             workspace::list_personal_workspaces,
             workspace::list_team_workspaces,
@@ -95,8 +159,29 @@ pub fn run() {
             workspace::create_workspace,
             workspace::rename_workspace,
             workspace::delete_workspace,
+            workspace::reorder_workspaces,
+            workspace::set_workspace_pinned,
+            workspace::update_workspace_appearance,
+            workspace::archive_workspace,
+            workspace::unarchive_workspace,
+            workspace::restore_workspace,
+            workspace::list_workspace_trash,
+            workspace::purge_workspace,
             workspace::get_team_workspace_creation_path,
             workspace::create_team_workspace,
+            workspace_sync::sync_workspaces,
+            workspace_sync::resolve_workspace_conflict,
+            entitlements::get_entitlements,
+            entitlements::refresh_entitlements,
+            workspace_session::get_workspace_session,
+            workspace_session::save_workspace_session,
+            team_cache::get_team_access,
+            team_cache::has_permission,
+            workspace_template::list_workspace_templates,
+            workspace_template::capture_workspace_template,
+            workspace_template::delete_workspace_template,
+            workspace_storage::get_workspace_data_dirs,
+            workspace_storage::clear_workspace_cache,
             desktop_attach::toggle_desktop_mode,
             desktop_attach::is_desktop_mode,
             desktop_attach::set_desktop_mode,
@@ -160,6 +245,22 @@ pub fn run() {
         .setup(|app| {
             let alarm_state = alarm::load_alarm_manager(app.handle());
             app.manage(std::sync::Mutex::new(alarm_state));
+            app.manage(account::NicknameAvailabilityCache::default());
+            app.manage(preferences::PreferencesService::load(app.handle()));
+            app.manage(workspace::WorkspaceSubscribers::default());
+            alarm::subscribe_to_preferences(app.handle());
+            alarm::subscribe_to_workspaces(app.handle());
+            workspace_session::subscribe_to_workspaces(app.handle());
+            team_cache::subscribe_to_workspaces(app.handle());
+            workspace_storage::subscribe_to_workspaces(app.handle());
+            if let Err(e) = workspace::purge_expired_trash(app.handle()) {
+                log::warn!("workspace: trash purge failed: {}", e);
+            }
+            if let Err(e) = workspace_storage::prune_orphans(app.handle()) {
+                log::warn!("workspace storage: orphan check failed: {}", e);
+            }
+            preferences::start_preferences_watcher(app.handle().clone());
+            entitlements::refresh_in_background(app.handle());
 
             // Logging
             app.handle().plugin(
@@ -290,6 +391,7 @@ pub fn run() {
             }
 
             alarm::start_alarm_scheduler(app.handle().clone());
+            backup::start_backup_scheduler(app.handle().clone());
 
                         if let Some(window) = app.get_webview_window("main") {
                 let _ = window.show();
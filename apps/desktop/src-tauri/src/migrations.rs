@@ -0,0 +1,373 @@
+/// Schema versions and the migration registry for persisted JSON files.
+///
+/// Files are stored in a versioned envelope:
+///
+/// ```json
+/// { "schema_version": 1, "data": { ... } }
+/// ```
+///
+/// Files written before versioning have no envelope and are treated as
+/// version 0. On load, `upgrade` runs each registered step in order until the
+/// data reaches the schema's current version.
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Upgrades the `data` of a file by exactly one version.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+pub struct Schema {
+    pub file: &'static str,
+    pub version: u32,
+    /// `migrations[n]` upgrades data from version `n` to `n + 1`.
+    pub migrations: &'static [Migration],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationError {
+    /// Written by a newer app version; left untouched.
+    UnsupportedVersion { found: u32, supported: u32 },
+    Failed { from: u32, message: String },
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "schema version {} is newer than supported version {}",
+                found, supported
+            ),
+            MigrationError::Failed { from, message } => {
+                write!(f, "migration from version {} failed: {}", from, message)
+            }
+        }
+    }
+}
+
+/// Unversioned files already match the v1 data layout; v1 only adds the envelope.
+fn wrap_legacy(data: Value) -> Result<Value, String> {
+    Ok(data)
+}
+
+pub const ACCOUNT_SETTINGS: Schema = Schema {
+    file: "account_settings.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+pub const USER_PREFERENCES: Schema = Schema {
+    file: "user_preferences.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+pub const AUTH_SESSION: Schema = Schema {
+    file: "auth_session.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+/// Namespace for ids derived from pre-v2 workspace ids.
+const LEGACY_WORKSPACE_NAMESPACE: Uuid = Uuid::from_u128(0x6d1c_5b0e_8f3a_4c27_9e41_2b7d_0a95_c3f8);
+/// Team ids at or above this were made up from the clock by v1 builds; real
+/// server team ids are small sequence numbers.
+const FABRICATED_TEAM_ID_MIN: i64 = 1_000_000_000_000;
+
+/// v2: `ws_<millis>` ids could collide and were the only identity. Each
+/// workspace gets a UUID-based id derived from its old one (so backups and
+/// exports of the same state migrate to the same ids), `ws_srv_<n>` ids fill
+/// in `server_id`, and clock-made team ids are dropped. A duplicated old id
+/// gets a distinct new id per occurrence; `current_workspace_id` follows the
+/// first one.
+fn workspace_ids_v2(mut data: Value) -> Result<Value, String> {
+    let current = data
+        .get("current_workspace_id")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let Some(workspaces) = data.get_mut("workspaces").and_then(Value::as_array_mut) else {
+        return Ok(data);
+    };
+
+    let mut seen = HashSet::new();
+    let mut new_current = None;
+    for (index, ws) in workspaces.iter_mut().enumerate() {
+        let ws = ws
+            .as_object_mut()
+            .ok_or_else(|| format!("workspace {} is not an object", index))?;
+        let old_id = ws.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+        let seed = if seen.insert(old_id.clone()) {
+            old_id.clone()
+        } else {
+            format!("{}#{}", old_id, index)
+        };
+        let new_id = format!(
+            "ws_{}",
+            Uuid::new_v5(&LEGACY_WORKSPACE_NAMESPACE, seed.as_bytes())
+        );
+        if new_current.is_none() && current.as_deref() == Some(old_id.as_str()) {
+            new_current = Some(new_id.clone());
+        }
+        ws.insert("id".to_string(), json!(new_id));
+
+        let server_id = ws.get("server_id").and_then(Value::as_i64).or_else(|| {
+            old_id
+                .strip_prefix("ws_srv_")
+                .and_then(|n| n.parse::<i64>().ok())
+        });
+        ws.insert("server_id".to_string(), json!(server_id));
+
+        if ws
+            .get("team_id")
+            .and_then(Value::as_i64)
+            .is_some_and(|id| id >= FABRICATED_TEAM_ID_MIN)
+        {
+            ws.insert("team_id".to_string(), Value::Null);
+        }
+    }
+
+    if let Some(new_current) = new_current {
+        data["current_workspace_id"] = json!(new_current);
+    }
+    Ok(data)
+}
+
+pub const WORKSPACE_STATE: Schema = Schema {
+    file: "workspace_state.json",
+    version: 2,
+    migrations: &[wrap_legacy, workspace_ids_v2],
+};
+
+pub const ALARM_STATE: Schema = Schema {
+    file: "alarm_state.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+pub const WINDOW_STATE: Schema = Schema {
+    file: "window_state.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+pub const WORKSPACE_SESSIONS: Schema = Schema {
+    file: "workspace_sessions.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+pub const WORKSPACE_TEMPLATES: Schema = Schema {
+    file: "workspace_templates.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+/// Derived from the server; safe to delete.
+pub const TEAM_CACHE: Schema = Schema {
+    file: "team_cache.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+/// Derived from the server; safe to delete.
+pub const ENTITLEMENTS_CACHE: Schema = Schema {
+    file: "entitlements_cache.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+/// Always read from the default config dir; points at a custom data dir.
+pub const DATA_LOCATION: Schema = Schema {
+    file: "data_location.json",
+    version: 1,
+    migrations: &[wrap_legacy],
+};
+
+/// Version a document was stored with; legacy files without an envelope are 0.
+pub fn stored_version(document: &Value) -> u32 {
+    match document {
+        Value::Object(map) if map.contains_key("schema_version") && map.contains_key("data") => map
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32,
+        _ => 0,
+    }
+}
+
+/// Split a stored document into `(version, data)`.
+fn unwrap_envelope(document: Value) -> (u32, Value) {
+    let version = stored_version(&document);
+    match document {
+        Value::Object(mut map) if map.contains_key("schema_version") && map.contains_key("data") => {
+            (version, map.remove("data").unwrap_or(Value::Null))
+        }
+        other => (0, other),
+    }
+}
+
+pub fn wrap(schema: &Schema, data: Value) -> Value {
+    json!({
+        "schema_version": schema.version,
+        "data": data,
+    })
+}
+
+/// Upgrade a stored document to the current version. Returns the data and
+/// the version it was stored with.
+pub fn upgrade(schema: &Schema, document: Value) -> Result<(Value, u32), MigrationError> {
+    let (stored_version, mut data) = unwrap_envelope(document);
+    if stored_version > schema.version {
+        return Err(MigrationError::UnsupportedVersion {
+            found: stored_version,
+            supported: schema.version,
+        });
+    }
+
+    for from in stored_version..schema.version {
+        let step = schema
+            .migrations
+            .get(from as usize)
+            .ok_or_else(|| MigrationError::Failed {
+                from,
+                message: format!("no migration registered for {}", schema.file),
+            })?;
+        data = step(data).map_err(|message| MigrationError::Failed { from, message })?;
+    }
+    Ok((data, stored_version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+
+    fn load<T: DeserializeOwned>(schema: &Schema, fixture: &str) -> (T, u32) {
+        let document: Value = serde_json::from_str(fixture).expect("fixture is valid json");
+        let (data, from) = upgrade(schema, document).expect("fixture upgrades");
+        (serde_json::from_value(data).expect("upgraded data matches the struct"), from)
+    }
+
+    #[test]
+    fn every_schema_has_a_step_per_prior_version() {
+        for schema in [
+            &ACCOUNT_SETTINGS,
+            &USER_PREFERENCES,
+            &AUTH_SESSION,
+            &WORKSPACE_STATE,
+            &ALARM_STATE,
+            &WINDOW_STATE,
+            &WORKSPACE_SESSIONS,
+            &WORKSPACE_TEMPLATES,
+            &ENTITLEMENTS_CACHE,
+            &TEAM_CACHE,
+            &DATA_LOCATION,
+        ] {
+            assert_eq!(schema.migrations.len(), schema.version as usize, "{}", schema.file);
+        }
+    }
+
+    #[test]
+    fn account_settings_v0() {
+        let (settings, from): (crate::account::AccountSettings, u32) = load(
+            &ACCOUNT_SETTINGS,
+            include_str!("../tests/fixtures/migrations/account_settings.v0.json"),
+        );
+        assert_eq!(from, 0);
+        assert_eq!(settings.nickname, "홍길동");
+        assert_eq!(settings.reserved_nicknames, vec!["admin".to_string()]);
+    }
+
+    #[test]
+    fn user_preferences_v0() {
+        let (preferences, from): (crate::preferences::UserPreferences, u32) = load(
+            &USER_PREFERENCES,
+            include_str!("../tests/fixtures/migrations/user_preferences.v0.json"),
+        );
+        assert_eq!(from, 0);
+        assert_eq!(preferences.language, "en");
+        assert_eq!(preferences.clock_format, "24h");
+    }
+
+    #[test]
+    fn auth_session_v0() {
+        let (session, from): (crate::account::AuthSession, u32) = load(
+            &AUTH_SESSION,
+            include_str!("../tests/fixtures/migrations/auth_session.v0.json"),
+        );
+        assert_eq!(from, 0);
+        assert_eq!(session.member_id.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn workspace_state_v0() {
+        let (state, from): (crate::workspace::WorkspaceState, u32) = load(
+            &WORKSPACE_STATE,
+            include_str!("../tests/fixtures/migrations/workspace_state.v0.json"),
+        );
+        assert_eq!(from, 0);
+        assert_eq!(state.workspaces.len(), 2);
+        assert_eq!(state.current_workspace_id, Some(state.workspaces[0].id.clone()));
+        assert_eq!(state.workspaces[1].team_id, Some(7));
+    }
+
+    #[test]
+    fn workspace_state_v1_gets_collision_free_ids() {
+        let fixture = include_str!("../tests/fixtures/migrations/workspace_state.v1.json");
+        let (state, from): (crate::workspace::WorkspaceState, u32) = load(&WORKSPACE_STATE, fixture);
+        assert_eq!(from, 1);
+
+        let ids: HashSet<&str> = state.workspaces.iter().map(|ws| ws.id.as_str()).collect();
+        assert_eq!(ids.len(), 4, "duplicated ids are split");
+        assert!(state.workspaces.iter().all(|ws| !ws.id.starts_with("ws_17")));
+        // The first of the two colliding workspaces stays current.
+        assert_eq!(state.current_workspace_id, Some(state.workspaces[0].id.clone()));
+        assert_eq!(state.workspaces[2].team_id, None);
+        assert_eq!(state.workspaces[3].server_id, Some(12));
+
+        // Deterministic, so a backup of the same file migrates to the same ids.
+        let (again, _): (crate::workspace::WorkspaceState, u32) = load(&WORKSPACE_STATE, fixture);
+        assert_eq!(again.workspaces[1].id, state.workspaces[1].id);
+    }
+
+    #[test]
+    fn alarm_state_v0() {
+        let (state, from): (crate::alarm::AlarmManagerState, u32) = load(
+            &ALARM_STATE,
+            include_str!("../tests/fixtures/migrations/alarm_state.v0.json"),
+        );
+        assert_eq!(from, 0);
+        assert!(!state.notifications_enabled);
+        assert_eq!(state.alarms.len(), 1);
+    }
+
+    #[test]
+    fn window_state_v0() {
+        let (state, from): (crate::position::WindowState, u32) = load(
+            &WINDOW_STATE,
+            include_str!("../tests/fixtures/migrations/window_state.v0.json"),
+        );
+        assert_eq!(from, 0);
+        assert_eq!(state.opacity, 100.0);
+    }
+
+    #[test]
+    fn current_version_loads_without_migration() {
+        let document = wrap(&WINDOW_STATE, json!({ "x": 1.0, "y": 2.0 }));
+        let (state, from): (crate::position::WindowState, u32) =
+            load(&WINDOW_STATE, &document.to_string());
+        assert_eq!(from, WINDOW_STATE.version);
+        assert_eq!(state.y, 2.0);
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let document = json!({ "schema_version": WINDOW_STATE.version + 1, "data": {} });
+        assert_eq!(
+            upgrade(&WINDOW_STATE, document),
+            Err(MigrationError::UnsupportedVersion {
+                found: WINDOW_STATE.version + 1,
+                supported: WINDOW_STATE.version,
+            })
+        );
+    }
+}
@@ -0,0 +1,316 @@
+/// Nickname validation — NFKC normalization, disallowed character classes and
+/// confusable-skeleton comparison against reserved nicknames.
+///
+/// Errors are serialized with a stable `code` so the UI can localize them.
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+
+const MIN_LEN: usize = 2;
+const MAX_LEN: usize = 20;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum NicknameError {
+    Empty,
+    TooShort { min: usize },
+    TooLong { max: usize },
+    InvisibleCharacter { codepoint: String },
+    UnsupportedCharacter { character: String },
+    IncompleteHangul { character: String },
+    Reserved { conflicts_with: String },
+}
+
+impl std::fmt::Display for NicknameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NicknameError::Empty => write!(f, "Nickname is required"),
+            NicknameError::TooShort { min } => {
+                write!(f, "Nickname must be at least {} characters", min)
+            }
+            NicknameError::TooLong { max } => {
+                write!(f, "Nickname must be {} characters or less", max)
+            }
+            NicknameError::InvisibleCharacter { codepoint } => {
+                write!(f, "Nickname contains an invisible character ({})", codepoint)
+            }
+            NicknameError::UnsupportedCharacter { character } => {
+                write!(f, "Nickname contains an unsupported character '{}'", character)
+            }
+            NicknameError::IncompleteHangul { character } => {
+                write!(f, "Nickname contains an incomplete Hangul letter '{}'", character)
+            }
+            NicknameError::Reserved { .. } => write!(f, "Nickname is already in use"),
+        }
+    }
+}
+
+/// Zero-width, bidi-control and filler code points that render as nothing.
+/// Hangul fillers are `Lo`, so they would otherwise pass the letter check.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{061C}'
+            | '\u{115F}'
+            | '\u{1160}'
+            | '\u{17B4}'
+            | '\u{17B5}'
+            | '\u{180B}'..='\u{180F}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+            | '\u{E0000}'..='\u{E007F}'
+            | '\u{E0100}'..='\u{E01EF}'
+    ) || c.is_control()
+}
+
+/// Conjoining jamo that survive NFKC composition, i.e. letters that could not
+/// be folded into a complete syllable.
+fn is_conjoining_jamo(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}' | '\u{A960}'..='\u{A97F}' | '\u{D7B0}'..='\u{D7FF}'
+    )
+}
+
+fn is_allowed_punctuation(c: char) -> bool {
+    matches!(c, ' ' | '_' | '-' | '.')
+}
+
+/// NFKC-normalize, trim and collapse internal whitespace.
+pub fn normalize_nickname(nickname: &str) -> String {
+    let composed: String = nickname.nfkc().collect();
+    composed.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn validate_nickname(nickname: &str) -> Result<String, NicknameError> {
+    // Invisible characters are checked on the raw input: NFKC drops some of
+    // them silently and the user should know their input was altered.
+    if let Some(c) = nickname.chars().find(|c| is_invisible(*c) && !c.is_whitespace()) {
+        return Err(NicknameError::InvisibleCharacter {
+            codepoint: format!("U+{:04X}", c as u32),
+        });
+    }
+
+    let normalized = normalize_nickname(nickname);
+    if normalized.is_empty() {
+        return Err(NicknameError::Empty);
+    }
+
+    for c in normalized.chars() {
+        if is_invisible(c) {
+            return Err(NicknameError::InvisibleCharacter {
+                codepoint: format!("U+{:04X}", c as u32),
+            });
+        }
+        if is_conjoining_jamo(c) {
+            return Err(NicknameError::IncompleteHangul {
+                character: c.to_string(),
+            });
+        }
+        if !c.is_alphanumeric() && !is_allowed_punctuation(c) {
+            return Err(NicknameError::UnsupportedCharacter {
+                character: c.to_string(),
+            });
+        }
+    }
+
+    let len = normalized.chars().count();
+    if len < MIN_LEN {
+        return Err(NicknameError::TooShort { min: MIN_LEN });
+    }
+    if len > MAX_LEN {
+        return Err(NicknameError::TooLong { max: MAX_LEN });
+    }
+    Ok(normalized)
+}
+
+/// Map a lowercase character to the Latin letter it is commonly mistaken for.
+fn confusable_base(c: char) -> char {
+    match c {
+        // Cyrillic
+        'а' => 'a',
+        'в' => 'b',
+        'с' => 'c',
+        'ԁ' => 'd',
+        'е' | 'ё' => 'e',
+        'һ' | 'н' => 'h',
+        'і' | 'ї' => 'l',
+        'ј' => 'j',
+        'к' => 'k',
+        'ӏ' => 'l',
+        'м' => 'm',
+        'о' => 'o',
+        'р' => 'p',
+        'ԛ' => 'q',
+        'ѕ' => 's',
+        'т' => 't',
+        'у' => 'y',
+        'х' => 'x',
+        'ԝ' => 'w',
+        // Greek
+        'α' => 'a',
+        'β' => 'b',
+        'ϲ' => 'c',
+        'ε' => 'e',
+        'η' => 'n',
+        'ι' => 'l',
+        'κ' => 'k',
+        'ν' => 'v',
+        'ο' => 'o',
+        'ρ' => 'p',
+        'τ' => 't',
+        'υ' => 'u',
+        'χ' => 'x',
+        // Latin / digits
+        'i' | '1' | '|' => 'l',
+        '0' => 'o',
+        '5' => 's',
+        _ => c,
+    }
+}
+
+/// Comparison key for "looks the same" checks: normalized, case-folded,
+/// separators removed and confusable letters mapped onto a common base.
+pub fn nickname_skeleton(nickname: &str) -> String {
+    let folded: String = normalize_nickname(nickname)
+        .to_lowercase()
+        .chars()
+        .filter(|c| !is_allowed_punctuation(*c))
+        .map(confusable_base)
+        .collect();
+    folded.replace("rn", "m").replace("vv", "w")
+}
+
+/// Returns the reserved entry that `nickname` is confusable with, if any.
+pub fn find_reserved_conflict<'a>(
+    nickname: &str,
+    reserved: &'a [String],
+) -> Option<&'a String> {
+    let skeleton = nickname_skeleton(nickname);
+    reserved
+        .iter()
+        .find(|used| nickname_skeleton(used) == skeleton)
+}
+
+pub fn is_same_nickname(a: &str, b: &str) -> bool {
+    nickname_skeleton(a) == nickname_skeleton(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invisible(codepoint: &str) -> Result<String, NicknameError> {
+        Err(NicknameError::InvisibleCharacter {
+            codepoint: codepoint.to_string(),
+        })
+    }
+
+    #[test]
+    fn normalizes_width_and_whitespace() {
+        assert_eq!(normalize_nickname("  ＰＥＣＡＬ　１２  "), "PECAL 12");
+        assert_eq!(normalize_nickname("홍  길동"), "홍 길동");
+        // Conjoining jamo compose into a syllable.
+        assert_eq!(normalize_nickname("\u{1112}\u{1169}\u{11BC}길동"), "홍길동");
+        assert_eq!(validate_nickname(" 홍길동 "), Ok("홍길동".to_string()));
+    }
+
+    #[test]
+    fn rejects_by_length() {
+        assert_eq!(validate_nickname("   "), Err(NicknameError::Empty));
+        assert_eq!(
+            validate_nickname("가"),
+            Err(NicknameError::TooShort { min: MIN_LEN })
+        );
+        assert_eq!(
+            validate_nickname(&"가".repeat(MAX_LEN + 1)),
+            Err(NicknameError::TooLong { max: MAX_LEN })
+        );
+        assert!(validate_nickname(&"가".repeat(MAX_LEN)).is_ok());
+    }
+
+    #[test]
+    fn rejects_invisible_characters() {
+        assert_eq!(validate_nickname("pe\u{200B}cal"), invisible("U+200B"));
+        assert_eq!(validate_nickname("pecal\u{FEFF}"), invisible("U+FEFF"));
+        assert_eq!(validate_nickname("\u{202E}lacep"), invisible("U+202E"));
+        assert_eq!(validate_nickname("pe\u{00AD}cal"), invisible("U+00AD"));
+        // Hangul fillers are letters by category.
+        assert_eq!(validate_nickname("\u{3164}홍길동"), invisible("U+3164"));
+        assert_eq!(validate_nickname("홍\u{FFA0}길동"), invisible("U+FFA0"));
+        assert_eq!(validate_nickname("pecal\u{E0041}"), invisible("U+E0041"));
+    }
+
+    #[test]
+    fn rejects_incomplete_hangul() {
+        // Compatibility jamo fold to conjoining jamo under NFKC.
+        assert_eq!(
+            validate_nickname("ㄱㄴ다"),
+            Err(NicknameError::IncompleteHangul {
+                character: "\u{1100}".to_string(),
+            })
+        );
+        assert_eq!(
+            validate_nickname("홍길\u{1103}"),
+            Err(NicknameError::IncompleteHangul {
+                character: "\u{1103}".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_characters() {
+        assert_eq!(
+            validate_nickname("pecal!"),
+            Err(NicknameError::UnsupportedCharacter {
+                character: "!".to_string(),
+            })
+        );
+        assert_eq!(
+            validate_nickname("pecal😀"),
+            Err(NicknameError::UnsupportedCharacter {
+                character: "😀".to_string(),
+            })
+        );
+        assert!(validate_nickname("pe_cal-1.0 kr").is_ok());
+    }
+
+    #[test]
+    fn skeleton_collisions() {
+        assert!(is_same_nickname("pecal", "peca1"));
+        assert!(is_same_nickname("pecal", "PECAI"));
+        assert!(is_same_nickname("pecal", "ｐｅｃａｌ"));
+        assert!(is_same_nickname("pecal", "pe_cal"));
+        assert!(is_same_nickname("pecal", "p.e-c a l"));
+        // Cyrillic р, е, с, а
+        assert!(is_same_nickname(
+            "pecal",
+            "\u{0440}\u{0435}\u{0441}\u{0430}l"
+        ));
+        assert!(is_same_nickname("modern", "rnodern"));
+        assert!(is_same_nickname("wow", "vvow"));
+        assert!(is_same_nickname("s0l0", "solo"));
+        assert!(!is_same_nickname("pecal", "pecan"));
+        assert!(!is_same_nickname("홍길동", "홍길순"));
+    }
+
+    #[test]
+    fn finds_the_reserved_entry() {
+        let reserved = vec!["admin".to_string(), "pecal".to_string()];
+        assert_eq!(
+            find_reserved_conflict("Peca1", &reserved),
+            Some(&reserved[1])
+        );
+        assert_eq!(
+            find_reserved_conflict("ａｄｍｉｎ", &reserved),
+            Some(&reserved[0])
+        );
+        assert_eq!(find_reserved_conflict("someone", &reserved), None);
+    }
+}
@@ -4,6 +4,8 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::error::{AppError, AppResult};
+
 /// OAuth 콜백 데이터
 #[derive(Clone, serde::Serialize)]
 pub struct OAuthCallback {
@@ -18,14 +20,17 @@ pub struct OAuthCallback {
 
 /// 로컬 HTTP 서버를 시작하고 OAuth 콜백 URL을 반환
 #[tauri::command]
-pub async fn start_oauth_server(app: AppHandle) -> Result<u16, String> {
+pub async fn start_oauth_server(app: AppHandle) -> AppResult<u16> {
     // 사용 가능한 포트 찾기
-    let listener = TcpListener::bind("127.0.0.1:0")
-        .map_err(|e| format!("Failed to bind to localhost: {}", e))?;
+    let listener = TcpListener::bind("127.0.0.1:0").map_err(|e| {
+        AppError::io("oauth_bind_failed", format!("Failed to bind to localhost: {}", e))
+    })?;
 
     let port = listener
         .local_addr()
-        .map_err(|e| format!("Failed to get local address: {}", e))?
+        .map_err(|e| {
+            AppError::io("oauth_bind_failed", format!("Failed to get local address: {}", e))
+        })?
         .port();
 
     println!("🚀 OAuth 서버 시작: http://127.0.0.1:{}", port);
@@ -37,7 +42,9 @@ pub async fn start_oauth_server(app: AppHandle) -> Result<u16, String> {
     // 논블로킹 모드로 설정 (타임아웃 처리용)
     listener
         .set_nonblocking(true)
-        .map_err(|e| format!("Failed to set non-blocking: {}", e))?;
+        .map_err(|e| {
+            AppError::io("oauth_bind_failed", format!("Failed to set non-blocking: {}", e))
+        })?;
 
     // 별도 스레드에서 콜백 대기
     std::thread::spawn(move || {
@@ -2,11 +2,13 @@
 ///
 /// Stored as a small JSON file in the Tauri app-config directory.
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::PathBuf;
 use tauri::Manager;
 
-const STATE_FILE: &str = "window_state.json";
+use crate::config_store;
+use crate::migrations;
+
+const STATE_FILE: &str = migrations::WINDOW_STATE.file;
 const MIN_WIDTH: f64 = 760.0;
 const MIN_HEIGHT: f64 = 600.0;
 
@@ -27,42 +29,43 @@ fn default_height() -> f64 { 660.0 }
 fn default_opacity() -> f64 { 100.0 }
 
 fn state_path(app: &tauri::AppHandle) -> Option<PathBuf> {
-    app.path()
-        .app_config_dir()
-        .ok()
-        .map(|dir| dir.join(STATE_FILE))
+    config_store::config_path(app, STATE_FILE).ok()
 }
 
 /// 디스크에서 저장된 상태 읽기 (이전 형식도 호환)
 pub fn load_state(app: &tauri::AppHandle) -> Option<WindowState> {
     let path = state_path(app)?;
-    let data = fs::read_to_string(&path).ok()?;
-    serde_json::from_str(&data).ok()
+    config_store::read(&path, &migrations::WINDOW_STATE)
 }
 
 /// 현재 상태를 디스크에 저장
 pub fn save_state(app: &tauri::AppHandle, state: &WindowState) {
     if let Some(path) = state_path(app) {
-        if let Some(parent) = path.parent() {
-            let _ = fs::create_dir_all(parent);
-        }
-        if let Ok(json) = serde_json::to_string_pretty(state) {
-            let _ = fs::write(&path, json);
+        if let Err(e) = config_store::write(&path, &migrations::WINDOW_STATE, state) {
+            log::warn!("state: failed to save window state: {}", e);
         }
     }
 }
 
-/// 현재 저장된 상태를 읽고 일부 필드만 업데이트하여 저장
+/// 현재 저장된 상태를 읽고 일부 필드만 업데이트하여 저장 (파일 잠금 유지)
 fn update_state(app: &tauri::AppHandle, f: impl FnOnce(&mut WindowState)) {
-    let mut state = load_state(app).unwrap_or(WindowState {
-        x: 100.0,
-        y: 100.0,
-        width: default_width(),
-        height: default_height(),
-        opacity: default_opacity(),
+    let Some(path) = state_path(app) else {
+        return;
+    };
+    let locked = config_store::with_lock(&path, || {
+        let mut state = load_state(app).unwrap_or(WindowState {
+            x: 100.0,
+            y: 100.0,
+            width: default_width(),
+            height: default_height(),
+            opacity: default_opacity(),
+        });
+        f(&mut state);
+        save_state(app, &state);
     });
-    f(&mut state);
-    save_state(app, &state);
+    if let Err(e) = locked {
+        log::warn!("state: skipped window state update: {}", e);
+    }
 }
 
 /// 위치 저장
@@ -0,0 +1,664 @@
+/// User preferences — schema, defaults, field-level validation and the
+/// preferences service that broadcasts changes.
+///
+/// Stored in `user_preferences.json` in the app config directory. Changes made
+/// through `save_user_preferences` or by editing the file on disk are emitted
+/// to every webview as `preferences://changed` and passed to Rust subscribers.
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::{Emitter, Manager};
+
+use crate::config_store;
+use crate::error::AppResult;
+use crate::migrations;
+
+const PREFERENCES_FILE: &str = migrations::USER_PREFERENCES.file;
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+const THEMES: &[&str] = &["system", "light", "dark"];
+const LANGUAGES: &[&str] = &["ko", "en"];
+const WEEK_STARTS: &[&str] = &["sunday", "monday"];
+const CLOCK_FORMATS: &[&str] = &["12h", "24h"];
+const DATE_FORMATS: &[&str] = &["YYYY-MM-DD", "YYYY.MM.DD", "MM/DD/YYYY", "DD/MM/YYYY"];
+pub const CALENDAR_VIEWS: &[&str] = &["day", "week", "month", "year", "table"];
+const MAX_REMINDER_OFFSETS: usize = 5;
+const MAX_REMINDER_OFFSET_MINUTES: i64 = 7 * 24 * 60;
+const MIN_TRASH_RETENTION_DAYS: i64 = 1;
+const MAX_TRASH_RETENTION_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkingHours {
+    #[serde(default = "default_working_start")]
+    pub start: String,
+    #[serde(default = "default_working_end")]
+    pub end: String,
+    /// Weekday numbers, 0 = Sunday.
+    #[serde(default = "default_working_days")]
+    pub days: Vec<u8>,
+    /// Korean public holidays count as days off.
+    #[serde(default = "default_exclude_holidays")]
+    pub exclude_holidays: bool,
+}
+
+/// Alarms due inside this daily window are held until it ends.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuietHours {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_quiet_start")]
+    pub start: String,
+    #[serde(default = "default_quiet_end")]
+    pub end: String,
+    /// Stay quiet all day on days off (weekends per working hours, holidays).
+    #[serde(default)]
+    pub include_days_off: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UserPreferences {
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    #[serde(default = "default_notifications_enabled")]
+    pub notifications_enabled: bool,
+    #[serde(default = "default_week_start")]
+    pub week_start: String,
+    #[serde(default = "default_clock_format")]
+    pub clock_format: String,
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    #[serde(default = "default_working_hours")]
+    pub working_hours: WorkingHours,
+    #[serde(default = "default_reminder_offsets")]
+    pub default_reminder_offsets: Vec<i64>,
+    #[serde(default = "default_task_color")]
+    pub default_task_color: String,
+    #[serde(default = "default_calendar_view")]
+    pub default_calendar_view: String,
+    #[serde(default = "default_quiet_hours")]
+    pub quiet_hours: QuietHours,
+    /// Emit a summary of the day's alarms every morning.
+    #[serde(default)]
+    pub daily_digest_enabled: bool,
+    /// Days a workspace stays in the trash before it is purged.
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreferenceFieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreferencesValidationError {
+    pub code: String,
+    pub fields: Vec<PreferenceFieldError>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PreferencesChangeSource {
+    Command,
+    File,
+    Import,
+    Restore,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreferenceFieldChange {
+    pub field: String,
+    pub previous: serde_json::Value,
+    pub current: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PreferencesChange {
+    pub source: PreferencesChangeSource,
+    pub changes: Vec<PreferenceFieldChange>,
+    pub preferences: UserPreferences,
+}
+
+impl PreferencesChange {
+    pub fn changed(&self, field: &str) -> bool {
+        self.changes.iter().any(|c| c.field == field)
+    }
+}
+
+pub type PreferencesSubscriber = Box<dyn Fn(&tauri::AppHandle, &PreferencesChange) + Send + Sync>;
+type SharedSubscriber = Arc<dyn Fn(&tauri::AppHandle, &PreferencesChange) + Send + Sync>;
+
+/// In-memory copy of the preferences plus the subscribers to notify on change.
+pub struct PreferencesService {
+    current: Mutex<UserPreferences>,
+    file_modified: Mutex<Option<SystemTime>>,
+    subscribers: Mutex<Vec<SharedSubscriber>>,
+}
+
+impl PreferencesValidationError {
+    fn storage(message: String) -> Self {
+        PreferencesValidationError {
+            code: "storage_error".to_string(),
+            fields: vec![PreferenceFieldError {
+                field: String::new(),
+                code: "storage_error".to_string(),
+                message,
+            }],
+        }
+    }
+}
+
+fn default_theme() -> String {
+    "system".to_string()
+}
+
+fn default_language() -> String {
+    "ko".to_string()
+}
+
+fn default_timezone() -> String {
+    "Asia/Seoul".to_string()
+}
+
+fn default_notifications_enabled() -> bool {
+    true
+}
+
+fn default_week_start() -> String {
+    "sunday".to_string()
+}
+
+fn default_clock_format() -> String {
+    "24h".to_string()
+}
+
+fn default_date_format() -> String {
+    "YYYY-MM-DD".to_string()
+}
+
+fn default_working_start() -> String {
+    "09:00".to_string()
+}
+
+fn default_working_end() -> String {
+    "18:00".to_string()
+}
+
+fn default_working_days() -> Vec<u8> {
+    vec![1, 2, 3, 4, 5]
+}
+
+fn default_exclude_holidays() -> bool {
+    true
+}
+
+fn default_working_hours() -> WorkingHours {
+    WorkingHours {
+        start: default_working_start(),
+        end: default_working_end(),
+        days: default_working_days(),
+        exclude_holidays: default_exclude_holidays(),
+    }
+}
+
+fn default_quiet_start() -> String {
+    "22:00".to_string()
+}
+
+fn default_quiet_end() -> String {
+    "08:00".to_string()
+}
+
+fn default_quiet_hours() -> QuietHours {
+    QuietHours {
+        enabled: false,
+        start: default_quiet_start(),
+        end: default_quiet_end(),
+        include_days_off: false,
+    }
+}
+
+fn default_reminder_offsets() -> Vec<i64> {
+    vec![10]
+}
+
+fn default_task_color() -> String {
+    "#3B82F6".to_string()
+}
+
+fn default_calendar_view() -> String {
+    "month".to_string()
+}
+
+fn default_trash_retention_days() -> i64 {
+    30
+}
+
+pub fn default_user_preferences() -> UserPreferences {
+    UserPreferences {
+        theme: default_theme(),
+        language: default_language(),
+        timezone: default_timezone(),
+        notifications_enabled: default_notifications_enabled(),
+        week_start: default_week_start(),
+        clock_format: default_clock_format(),
+        date_format: default_date_format(),
+        working_hours: default_working_hours(),
+        default_reminder_offsets: default_reminder_offsets(),
+        default_task_color: default_task_color(),
+        default_calendar_view: default_calendar_view(),
+        quiet_hours: default_quiet_hours(),
+        daily_digest_enabled: false,
+        trash_retention_days: default_trash_retention_days(),
+    }
+}
+
+/// Parse `HH:MM` (24h) into minutes since midnight.
+pub fn parse_clock_time(value: &str) -> Option<u32> {
+    let (h, m) = value.trim().split_once(':')?;
+    if h.len() != 2 || m.len() != 2 {
+        return None;
+    }
+    let h: u32 = h.parse().ok()?;
+    let m: u32 = m.parse().ok()?;
+    if h > 23 || m > 59 {
+        return None;
+    }
+    Some(h * 60 + m)
+}
+
+/// Normalize `#RGB` / `#RRGGBB` to uppercase `#RRGGBB`.
+pub fn normalize_hex_color(value: &str) -> Option<String> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let expanded = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    Some(format!("#{}", expanded.to_uppercase()))
+}
+
+pub fn is_valid_timezone(name: &str) -> bool {
+    name.parse::<chrono_tz::Tz>().is_ok()
+}
+
+fn one_of(
+    errors: &mut Vec<PreferenceFieldError>,
+    field: &str,
+    value: &str,
+    allowed: &[&str],
+) -> String {
+    let trimmed = value.trim();
+    if !allowed.contains(&trimmed) {
+        errors.push(PreferenceFieldError {
+            field: field.to_string(),
+            code: "unsupported_value".to_string(),
+            message: format!("{} must be one of: {}", field, allowed.join(", ")),
+        });
+    }
+    trimmed.to_string()
+}
+
+fn field_error(errors: &mut Vec<PreferenceFieldError>, field: &str, code: &str, message: &str) {
+    errors.push(PreferenceFieldError {
+        field: field.to_string(),
+        code: code.to_string(),
+        message: message.to_string(),
+    });
+}
+
+/// Report each of `<group>.start` / `<group>.end` that is not a valid time.
+fn invalid_times(
+    errors: &mut Vec<PreferenceFieldError>,
+    group: &str,
+    start: Option<u32>,
+    end: Option<u32>,
+) {
+    for (bound, value) in [("start", start), ("end", end)] {
+        if value.is_none() {
+            let field = format!("{}.{}", group, bound);
+            field_error(
+                errors,
+                &field,
+                "invalid_time",
+                &format!("{} must be in HH:MM format", field),
+            );
+        }
+    }
+}
+
+/// Validate every field and return a normalized copy, or all field errors at once.
+pub fn validate_preferences(
+    preferences: &UserPreferences,
+) -> Result<UserPreferences, PreferencesValidationError> {
+    let mut errors = Vec::new();
+    let mut normalized = preferences.clone();
+
+    normalized.theme = one_of(&mut errors, "theme", &preferences.theme, THEMES);
+    normalized.language = one_of(&mut errors, "language", &preferences.language, LANGUAGES);
+    normalized.week_start = one_of(&mut errors, "week_start", &preferences.week_start, WEEK_STARTS);
+    normalized.clock_format =
+        one_of(&mut errors, "clock_format", &preferences.clock_format, CLOCK_FORMATS);
+    normalized.date_format =
+        one_of(&mut errors, "date_format", &preferences.date_format, DATE_FORMATS);
+    normalized.default_calendar_view = one_of(
+        &mut errors,
+        "default_calendar_view",
+        &preferences.default_calendar_view,
+        CALENDAR_VIEWS,
+    );
+
+    normalized.timezone = preferences.timezone.trim().to_string();
+    if !is_valid_timezone(&normalized.timezone) {
+        field_error(
+            &mut errors,
+            "timezone",
+            "unknown_timezone",
+            "timezone must be an IANA time zone name such as Asia/Seoul",
+        );
+    }
+
+    let hours = &preferences.working_hours;
+    match (parse_clock_time(&hours.start), parse_clock_time(&hours.end)) {
+        (Some(start), Some(end)) if start < end => {}
+        (Some(_), Some(_)) => field_error(
+            &mut errors,
+            "working_hours",
+            "invalid_range",
+            "working_hours.start must be earlier than working_hours.end",
+        ),
+        (start, end) => invalid_times(&mut errors, "working_hours", start, end),
+    }
+    if hours.days.iter().any(|d| *d > 6) {
+        field_error(
+            &mut errors,
+            "working_hours.days",
+            "invalid_weekday",
+            "working_hours.days must contain weekday numbers from 0 (Sunday) to 6",
+        );
+    }
+    normalized.working_hours.start = hours.start.trim().to_string();
+    normalized.working_hours.end = hours.end.trim().to_string();
+    normalized.working_hours.days.sort_unstable();
+    normalized.working_hours.days.dedup();
+
+    let quiet = &preferences.quiet_hours;
+    match (parse_clock_time(&quiet.start), parse_clock_time(&quiet.end)) {
+        // Overnight windows are allowed, so only an empty window is rejected.
+        (Some(start), Some(end)) if start != end => {}
+        (Some(_), Some(_)) => field_error(
+            &mut errors,
+            "quiet_hours",
+            "invalid_range",
+            "quiet_hours.start and quiet_hours.end must differ",
+        ),
+        (start, end) => invalid_times(&mut errors, "quiet_hours", start, end),
+    }
+    normalized.quiet_hours.start = quiet.start.trim().to_string();
+    normalized.quiet_hours.end = quiet.end.trim().to_string();
+
+    let mut offsets = preferences.default_reminder_offsets.clone();
+    offsets.sort_unstable();
+    offsets.dedup();
+    if offsets
+        .iter()
+        .any(|m| *m < 0 || *m > MAX_REMINDER_OFFSET_MINUTES)
+    {
+        field_error(
+            &mut errors,
+            "default_reminder_offsets",
+            "out_of_range",
+            &format!(
+                "Reminder offsets must be between 0 and {} minutes",
+                MAX_REMINDER_OFFSET_MINUTES
+            ),
+        );
+    }
+    if offsets.len() > MAX_REMINDER_OFFSETS {
+        field_error(
+            &mut errors,
+            "default_reminder_offsets",
+            "too_many",
+            &format!(
+                "At most {} default reminders are allowed",
+                MAX_REMINDER_OFFSETS
+            ),
+        );
+    }
+    normalized.default_reminder_offsets = offsets;
+
+    if !(MIN_TRASH_RETENTION_DAYS..=MAX_TRASH_RETENTION_DAYS)
+        .contains(&preferences.trash_retention_days)
+    {
+        field_error(
+            &mut errors,
+            "trash_retention_days",
+            "out_of_range",
+            &format!(
+                "trash_retention_days must be between {} and {}",
+                MIN_TRASH_RETENTION_DAYS, MAX_TRASH_RETENTION_DAYS
+            ),
+        );
+    }
+
+    match normalize_hex_color(&preferences.default_task_color) {
+        Some(color) => normalized.default_task_color = color,
+        None => field_error(
+            &mut errors,
+            "default_task_color",
+            "invalid_color",
+            "default_task_color must be a hex color such as #3B82F6",
+        ),
+    }
+
+    if errors.is_empty() {
+        Ok(normalized)
+    } else {
+        Err(PreferencesValidationError {
+            code: "invalid_preferences".to_string(),
+            fields: errors,
+        })
+    }
+}
+
+fn preferences_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    config_store::config_path(app, PREFERENCES_FILE)
+}
+
+pub fn load_preferences(app: &tauri::AppHandle) -> UserPreferences {
+    let path = match preferences_path(app) {
+        Ok(path) => path,
+        Err(_) => return default_user_preferences(),
+    };
+    config_store::read::<UserPreferences>(&path, &migrations::USER_PREFERENCES).unwrap_or_else(default_user_preferences)
+}
+
+fn save_preferences(app: &tauri::AppHandle, preferences: &UserPreferences) -> Result<(), String> {
+    config_store::write_locked(app, &migrations::USER_PREFERENCES, preferences)
+}
+
+fn file_modified(app: &tauri::AppHandle) -> Option<SystemTime> {
+    let path = preferences_path(app).ok()?;
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn diff_preferences(previous: &UserPreferences, current: &UserPreferences) -> Vec<PreferenceFieldChange> {
+    let previous = serde_json::to_value(previous).unwrap_or_default();
+    let current = serde_json::to_value(current).unwrap_or_default();
+    let (Some(previous), Some(current)) = (previous.as_object(), current.as_object()) else {
+        return Vec::new();
+    };
+
+    current
+        .iter()
+        .filter(|(field, value)| previous.get(*field) != Some(*value))
+        .map(|(field, value)| PreferenceFieldChange {
+            field: field.clone(),
+            previous: previous.get(field).cloned().unwrap_or_default(),
+            current: value.clone(),
+        })
+        .collect()
+}
+
+impl PreferencesService {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        PreferencesService {
+            current: Mutex::new(load_preferences(app)),
+            file_modified: Mutex::new(file_modified(app)),
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn current(&self) -> UserPreferences {
+        self.current
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_else(|_| default_user_preferences())
+    }
+
+    /// Run `f` with the file watcher held off, e.g. while the data directory
+    /// moves; the file as it is afterwards counts as already seen.
+    pub fn pause_watcher<R>(&self, app: &tauri::AppHandle, f: impl FnOnce() -> R) -> R {
+        let Ok(mut known) = self.file_modified.lock() else {
+            return f();
+        };
+        let result = f();
+        *known = file_modified(app);
+        result
+    }
+
+    pub fn subscribe(&self, subscriber: PreferencesSubscriber) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(Arc::from(subscriber));
+        }
+    }
+
+    /// Replace the cached preferences and notify webviews and subscribers of
+    /// the fields that actually changed.
+    fn publish(
+        &self,
+        app: &tauri::AppHandle,
+        preferences: UserPreferences,
+        source: PreferencesChangeSource,
+    ) {
+        let previous = match self.current.lock() {
+            Ok(mut guard) => std::mem::replace(&mut *guard, preferences.clone()),
+            Err(_) => return,
+        };
+
+        let changes = diff_preferences(&previous, &preferences);
+        if changes.is_empty() {
+            return;
+        }
+
+        let change = PreferencesChange {
+            source,
+            changes,
+            preferences,
+        };
+        if let Err(e) = app.emit("preferences://changed", change.clone()) {
+            log::warn!("preferences: failed to emit change event: {}", e);
+        }
+        // Call outside the lock so a subscriber may subscribe or publish itself.
+        let subscribers = match self.subscribers.lock() {
+            Ok(guard) => guard.clone(),
+            Err(_) => return,
+        };
+        for subscriber in subscribers.iter() {
+            subscriber(app, &change);
+        }
+    }
+}
+
+/// Current preferences from the service, falling back to disk before setup.
+pub fn current_preferences(app: &tauri::AppHandle) -> UserPreferences {
+    match app.try_state::<PreferencesService>() {
+        Some(service) => service.current(),
+        None => load_preferences(app),
+    }
+}
+
+/// Poll the preferences file so manual edits are picked up while running.
+pub fn start_preferences_watcher(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(WATCH_INTERVAL);
+
+        let Some(service) = app.try_state::<PreferencesService>() else {
+            continue;
+        };
+        let modified = file_modified(&app);
+        {
+            let Ok(mut known) = service.file_modified.lock() else {
+                continue;
+            };
+            if *known == modified {
+                continue;
+            }
+            *known = modified;
+        }
+
+        let Some(path) = preferences_path(&app).ok().filter(|p| p.exists()) else {
+            continue;
+        };
+        // A half-written manual edit must not be quarantined, so parse
+        // without the store's recovery path.
+        let edited = match config_store::parse_file::<UserPreferences>(&path, &migrations::USER_PREFERENCES) {
+            Ok(edited) => edited,
+            Err(e) => {
+                log::warn!("preferences: ignoring unreadable edit to {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        match validate_preferences(&edited) {
+            Ok(preferences) => service.publish(&app, preferences, PreferencesChangeSource::File),
+            Err(e) => log::warn!(
+                "preferences: ignoring invalid edit to {} ({} field errors)",
+                path.display(),
+                e.fields.len()
+            ),
+        }
+    });
+}
+
+/// Validate, persist and broadcast new preferences.
+pub fn apply_preferences(
+    app: &tauri::AppHandle,
+    preferences: &UserPreferences,
+    source: PreferencesChangeSource,
+) -> Result<UserPreferences, PreferencesValidationError> {
+    let preferences = validate_preferences(preferences)?;
+    save_preferences(app, &preferences).map_err(PreferencesValidationError::storage)?;
+
+    if let Some(service) = app.try_state::<PreferencesService>() {
+        if let Ok(mut known) = service.file_modified.lock() {
+            *known = file_modified(app);
+        }
+        service.publish(app, preferences.clone(), source);
+    }
+    Ok(preferences)
+}
+
+#[tauri::command]
+pub fn get_user_preferences(app: tauri::AppHandle) -> UserPreferences {
+    current_preferences(&app)
+}
+
+/// Field errors are in `details.fields`.
+#[tauri::command]
+pub fn save_user_preferences(
+    app: tauri::AppHandle,
+    preferences: UserPreferences,
+) -> AppResult<UserPreferences> {
+    Ok(apply_preferences(&app, &preferences, PreferencesChangeSource::Command)?)
+}
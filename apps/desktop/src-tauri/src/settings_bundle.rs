@@ -0,0 +1,430 @@
+/// Settings export / import — moves the user's configuration to a new install
+/// as a single versioned JSON bundle.
+///
+/// Each file section is stored in the same `{ schema_version, data }` envelope
+/// as on disk, so a bundle from an older app version is upgraded through the
+/// migration registry on import. Secrets (the auth session) and device-local
+/// paths (the profile image) are never exported.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::account::{self, AccountSettings};
+use crate::alarm::{self, AlarmManagerState, AlarmPolicies};
+use crate::error::{AppError, AppResult};
+use crate::migrations::{self, Schema};
+use crate::nickname;
+use crate::position::{self, WindowState};
+use crate::preferences::{self, PreferencesChangeSource, UserPreferences};
+use crate::time::now_unix;
+use crate::workspace::{self, WorkspaceState};
+
+const BUNDLE_FORMAT: &str = "pecal-settings";
+const BUNDLE_VERSION: u32 = 1;
+const MAX_BUNDLE_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsSection {
+    Preferences,
+    AccountSettings,
+    WorkspaceState,
+    WindowState,
+    AlarmPolicies,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsBundle {
+    pub format: String,
+    pub bundle_version: u32,
+    pub app_version: String,
+    pub exported_at_unix: i64,
+    #[serde(default)]
+    pub preferences: Option<Value>,
+    #[serde(default)]
+    pub account_settings: Option<Value>,
+    #[serde(default)]
+    pub workspace_state: Option<Value>,
+    #[serde(default)]
+    pub window_state: Option<Value>,
+    #[serde(default)]
+    pub alarm_policies: Option<AlarmPolicies>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsExportReport {
+    pub path: String,
+    pub sections: Vec<SettingsSection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsFieldChange {
+    pub field: String,
+    pub current: Value,
+    pub incoming: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsSectionPreview {
+    pub section: SettingsSection,
+    pub changes: Vec<SettingsFieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsImportPreview {
+    pub app_version: String,
+    pub exported_at_unix: i64,
+    pub sections: Vec<SettingsSectionPreview>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsImportReport {
+    pub applied: Vec<SettingsSection>,
+    pub skipped: Vec<SettingsSection>,
+}
+
+/// A bundle that has been upgraded and validated, ready to apply.
+struct ParsedBundle {
+    app_version: String,
+    exported_at_unix: i64,
+    preferences: Option<UserPreferences>,
+    account_settings: Option<AccountSettings>,
+    workspace_state: Option<WorkspaceState>,
+    window_state: Option<WindowState>,
+    alarm_policies: Option<AlarmPolicies>,
+}
+
+fn invalid_section(section: SettingsSection, reason: impl Into<String>) -> AppError {
+    let reason = reason.into();
+    AppError::validation("settings_bundle_invalid", format!("Invalid settings bundle: {}", reason))
+        .with_details(serde_json::json!({ "section": section, "reason": reason }))
+}
+
+fn envelope<T: Serialize>(schema: &Schema, value: &T) -> AppResult<Value> {
+    let data = serde_json::to_value(value)
+        .map_err(|e| AppError::io("settings_export_failed", e.to_string()))?;
+    Ok(migrations::wrap(schema, data))
+}
+
+fn open_section<T: serde::de::DeserializeOwned>(
+    section: SettingsSection,
+    schema: &Schema,
+    document: Option<Value>,
+) -> AppResult<Option<T>> {
+    let Some(document) = document else {
+        return Ok(None);
+    };
+    let (data, _) = migrations::upgrade(schema, document)
+        .map_err(|e| invalid_section(section, e.to_string()))?;
+    serde_json::from_value(data)
+        .map(Some)
+        .map_err(|e| invalid_section(section, e.to_string()))
+}
+
+fn build_bundle(app: &tauri::AppHandle, alarm_state: &AlarmManagerState) -> AppResult<SettingsBundle> {
+    let mut account_settings = account::load_account_settings(app);
+    account_settings.profile_image_path = None;
+
+    Ok(SettingsBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        bundle_version: BUNDLE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at_unix: now_unix(),
+        preferences: Some(envelope(
+            &migrations::USER_PREFERENCES,
+            &preferences::current_preferences(app),
+        )?),
+        account_settings: Some(envelope(&migrations::ACCOUNT_SETTINGS, &account_settings)?),
+        workspace_state: Some(envelope(
+            &migrations::WORKSPACE_STATE,
+            &workspace::load_state(app),
+        )?),
+        window_state: position::load_state(app)
+            .map(|state| envelope(&migrations::WINDOW_STATE, &state))
+            .transpose()?,
+        alarm_policies: Some(alarm::alarm_policies(alarm_state)),
+    })
+}
+
+fn read_bundle(source_path: &str) -> AppResult<ParsedBundle> {
+    let path = PathBuf::from(source_path.trim());
+    let details = serde_json::json!({ "path": path.to_string_lossy() });
+    let metadata = fs::metadata(&path).map_err(|_| {
+        AppError::not_found("settings_bundle_missing", "Settings bundle does not exist")
+            .with_details(details.clone())
+    })?;
+    if metadata.len() > MAX_BUNDLE_BYTES {
+        return Err(AppError::validation(
+            "settings_bundle_too_large",
+            "Settings bundle is too large",
+        )
+        .with_details(details));
+    }
+
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| AppError::storage(format!("Failed to read settings bundle: {}", e)))?;
+    let bundle: SettingsBundle = serde_json::from_str(&raw).map_err(|e| {
+        AppError::validation(
+            "settings_bundle_unrecognized",
+            format!("File is not a Pecal settings bundle: {}", e),
+        )
+        .with_details(details.clone())
+    })?;
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(AppError::validation(
+            "settings_bundle_unrecognized",
+            "File is not a Pecal settings bundle",
+        )
+        .with_details(details));
+    }
+    if bundle.bundle_version > BUNDLE_VERSION {
+        return Err(AppError::validation(
+            "settings_bundle_unsupported_version",
+            "Settings bundle was exported by a newer version of Pecal",
+        )
+        .with_details(serde_json::json!({
+            "found": bundle.bundle_version,
+            "supported": BUNDLE_VERSION,
+            "app_version": bundle.app_version,
+        })));
+    }
+
+    let preferences = open_section::<UserPreferences>(
+        SettingsSection::Preferences,
+        &migrations::USER_PREFERENCES,
+        bundle.preferences,
+    )?
+    .map(|incoming| preferences::validate_preferences(&incoming))
+    .transpose()?;
+
+    let account_settings = open_section::<AccountSettings>(
+        SettingsSection::AccountSettings,
+        &migrations::ACCOUNT_SETTINGS,
+        bundle.account_settings,
+    )?
+    .map(|mut incoming| {
+        incoming.nickname = nickname::validate_nickname(&incoming.nickname)?;
+        Ok::<_, AppError>(incoming)
+    })
+    .transpose()?;
+
+    let workspace_state = open_section::<WorkspaceState>(
+        SettingsSection::WorkspaceState,
+        &migrations::WORKSPACE_STATE,
+        bundle.workspace_state,
+    )?
+    .map(workspace::validate_state)
+    .transpose()?;
+
+    let window_state = open_section::<WindowState>(
+        SettingsSection::WindowState,
+        &migrations::WINDOW_STATE,
+        bundle.window_state,
+    )?;
+
+    Ok(ParsedBundle {
+        app_version: bundle.app_version,
+        exported_at_unix: bundle.exported_at_unix,
+        preferences,
+        account_settings,
+        workspace_state,
+        window_state,
+        alarm_policies: bundle.alarm_policies,
+    })
+}
+
+/// Top-level fields whose value differs between the current and incoming data.
+fn diff_fields<T: Serialize>(current: &T, incoming: &T) -> Vec<SettingsFieldChange> {
+    let current = serde_json::to_value(current).unwrap_or_default();
+    let incoming = serde_json::to_value(incoming).unwrap_or_default();
+    let (Some(current), Some(incoming)) = (current.as_object(), incoming.as_object()) else {
+        return Vec::new();
+    };
+
+    incoming
+        .iter()
+        .filter(|(field, value)| current.get(*field) != Some(*value))
+        .map(|(field, value)| SettingsFieldChange {
+            field: field.clone(),
+            current: current.get(field).cloned().unwrap_or_default(),
+            incoming: value.clone(),
+        })
+        .collect()
+}
+
+fn wants(sections: &Option<Vec<SettingsSection>>, section: SettingsSection) -> bool {
+    sections
+        .as_ref()
+        .map(|selected| selected.contains(&section))
+        .unwrap_or(true)
+}
+
+fn write_bundle(path: &Path, bundle: &SettingsBundle) -> AppResult<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| {
+            AppError::storage(format!("Failed to create export directory: {}", e))
+        })?;
+    }
+    let json = serde_json::to_string_pretty(bundle)
+        .map_err(|e| AppError::io("settings_export_failed", e.to_string()))?;
+    fs::write(path, json)
+        .map_err(|e| AppError::storage(format!("Failed to write settings bundle: {}", e)))
+}
+
+#[tauri::command]
+pub fn export_settings(
+    app: tauri::AppHandle,
+    alarm_state: State<'_, Mutex<AlarmManagerState>>,
+    target_path: String,
+) -> AppResult<SettingsExportReport> {
+    let target = PathBuf::from(target_path.trim());
+    if target.as_os_str().is_empty() {
+        return Err(AppError::validation(
+            "export_path_required",
+            "Export path is required",
+        ));
+    }
+
+    let bundle = {
+        let guard = alarm_state.lock().map_err(|_| AppError::state_lock("alarm"))?;
+        build_bundle(&app, &guard)?
+    };
+    write_bundle(&target, &bundle)?;
+
+    let sections = [
+        (SettingsSection::Preferences, bundle.preferences.is_some()),
+        (SettingsSection::AccountSettings, bundle.account_settings.is_some()),
+        (SettingsSection::WorkspaceState, bundle.workspace_state.is_some()),
+        (SettingsSection::WindowState, bundle.window_state.is_some()),
+        (SettingsSection::AlarmPolicies, bundle.alarm_policies.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(section, present)| present.then_some(section))
+    .collect();
+
+    Ok(SettingsExportReport {
+        path: target.to_string_lossy().to_string(),
+        sections,
+    })
+}
+
+/// Validate a bundle and list what importing it would change. Nothing is written.
+#[tauri::command]
+pub fn preview_settings_import(
+    app: tauri::AppHandle,
+    alarm_state: State<'_, Mutex<AlarmManagerState>>,
+    source_path: String,
+) -> AppResult<SettingsImportPreview> {
+    let bundle = read_bundle(&source_path)?;
+    let mut sections = Vec::new();
+
+    if let Some(incoming) = &bundle.preferences {
+        sections.push(SettingsSectionPreview {
+            section: SettingsSection::Preferences,
+            changes: diff_fields(&preferences::current_preferences(&app), incoming),
+        });
+    }
+    if let Some(incoming) = &bundle.account_settings {
+        let mut current = account::load_account_settings(&app);
+        current.profile_image_path = None;
+        sections.push(SettingsSectionPreview {
+            section: SettingsSection::AccountSettings,
+            changes: diff_fields(&current, incoming),
+        });
+    }
+    if let Some(incoming) = &bundle.workspace_state {
+        sections.push(SettingsSectionPreview {
+            section: SettingsSection::WorkspaceState,
+            changes: diff_fields(&workspace::load_state(&app), incoming),
+        });
+    }
+    if let Some(incoming) = &bundle.window_state {
+        let current = position::load_state(&app)
+            .and_then(|state| serde_json::to_value(state).ok())
+            .unwrap_or_else(|| serde_json::json!({}));
+        let incoming = serde_json::to_value(incoming).unwrap_or_default();
+        sections.push(SettingsSectionPreview {
+            section: SettingsSection::WindowState,
+            changes: diff_fields(&current, &incoming),
+        });
+    }
+    if let Some(incoming) = &bundle.alarm_policies {
+        let guard = alarm_state.lock().map_err(|_| AppError::state_lock("alarm"))?;
+        sections.push(SettingsSectionPreview {
+            section: SettingsSection::AlarmPolicies,
+            changes: diff_fields(&alarm::alarm_policies(&guard), incoming),
+        });
+    }
+
+    Ok(SettingsImportPreview {
+        app_version: bundle.app_version,
+        exported_at_unix: bundle.exported_at_unix,
+        sections,
+    })
+}
+
+/// Apply a bundle through the normal save paths. `sections` limits the import
+/// to the given sections; all present sections are applied when omitted.
+/// The whole bundle is validated before anything is written.
+#[tauri::command]
+pub fn apply_settings_import(
+    app: tauri::AppHandle,
+    alarm_state: State<'_, Mutex<AlarmManagerState>>,
+    source_path: String,
+    sections: Option<Vec<SettingsSection>>,
+) -> AppResult<SettingsImportReport> {
+    let bundle = read_bundle(&source_path)?;
+    let mut report = SettingsImportReport {
+        applied: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut track = |section: SettingsSection, present: bool| {
+        let apply = present && wants(&sections, section);
+        if present && !apply {
+            report.skipped.push(section);
+        }
+        apply
+    };
+    let apply_preferences = track(SettingsSection::Preferences, bundle.preferences.is_some());
+    let apply_account = track(SettingsSection::AccountSettings, bundle.account_settings.is_some());
+    let apply_workspaces = track(SettingsSection::WorkspaceState, bundle.workspace_state.is_some());
+    let apply_window = track(SettingsSection::WindowState, bundle.window_state.is_some());
+    let apply_alarms = track(SettingsSection::AlarmPolicies, bundle.alarm_policies.is_some());
+
+    if let Some(incoming) = bundle.preferences.filter(|_| apply_preferences) {
+        preferences::apply_preferences(&app, &incoming, PreferencesChangeSource::Import)?;
+        report.applied.push(SettingsSection::Preferences);
+    }
+
+    if let Some(incoming) = bundle.account_settings.filter(|_| apply_account) {
+        // The profile image path points into the old machine's config dir.
+        account::update_account_settings(&app, |settings| {
+            settings.nickname = incoming.nickname;
+            settings.reserved_nicknames = incoming.reserved_nicknames;
+            Ok(())
+        })?;
+        report.applied.push(SettingsSection::AccountSettings);
+    }
+
+    if let Some(incoming) = bundle.workspace_state.filter(|_| apply_workspaces) {
+        workspace::replace_state(&app, incoming)?;
+        report.applied.push(SettingsSection::WorkspaceState);
+    }
+
+    // 창 위치/크기는 다음 실행 때 복원된다.
+    if let Some(incoming) = bundle.window_state.filter(|_| apply_window) {
+        position::save_state(&app, &incoming);
+        report.applied.push(SettingsSection::WindowState);
+    }
+
+    if let Some(incoming) = bundle.alarm_policies.filter(|_| apply_alarms) {
+        let mut guard = alarm_state.lock().map_err(|_| AppError::state_lock("alarm"))?;
+        alarm::apply_alarm_policies(&app, &mut guard, &incoming).map_err(AppError::storage)?;
+        report.applied.push(SettingsSection::AlarmPolicies);
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,304 @@
+/// Offline cache of team members, roles and the signed-in member's permission
+/// codes, per team workspace.
+///
+/// Entries are refreshed from the API once they are older than
+/// `TEAM_CACHE_TTL_SECS`; when the server is unreachable the cached entry is
+/// used as is, so permission checks keep working offline.
+///
+/// Permission codes come from `GET /api/teams/{id}/permissions/me`, which any
+/// member may call; owners get every code.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::account;
+use crate::api::{ApiClient, ApiError};
+use crate::config_store;
+use crate::error::{AppError, AppResult};
+use crate::migrations;
+use crate::time::now_unix;
+use crate::workspace::{self, WorkspaceChange, WorkspaceType};
+
+const CACHE_FILE: &str = migrations::TEAM_CACHE.file;
+const TEAM_CACHE_TTL_SECS: i64 = 10 * 60;
+
+/// Mirrors `PERMISSIONS` in the web app (`lib/permissions.ts`).
+pub const PERMISSION_CODES: &[&str] = &[
+    "TASK_CREATE",
+    "TASK_EDIT_OWN",
+    "TASK_EDIT_ALL",
+    "TASK_DELETE_OWN",
+    "TASK_DELETE_ALL",
+    "WORKSPACE_CREATE",
+    "WORKSPACE_EDIT",
+    "WORKSPACE_DELETE",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMemberInfo {
+    pub member_id: i64,
+    #[serde(default)]
+    pub nickname: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub role_name: Option<String>,
+    #[serde(default)]
+    pub role_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamRoleInfo {
+    pub team_role_id: i64,
+    pub name: String,
+    #[serde(default, rename = "memberCount")]
+    pub member_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamSnapshot {
+    pub team_id: i64,
+    #[serde(default)]
+    pub owner_member_id: Option<i64>,
+    #[serde(default)]
+    pub members: Vec<TeamMemberInfo>,
+    #[serde(default)]
+    pub roles: Vec<TeamRoleInfo>,
+    /// Role of the signed-in member.
+    #[serde(default)]
+    pub role_id: Option<i64>,
+    /// Codes granted to the signed-in member.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    pub fetched_at_unix: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TeamCache {
+    #[serde(default)]
+    pub member_id: Option<String>,
+    /// Keyed by team id.
+    #[serde(default)]
+    pub teams: HashMap<i64, TeamSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamAccess {
+    pub snapshot: TeamSnapshot,
+    pub is_owner: bool,
+    /// Served from the cache after a failed refresh.
+    pub stale: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct TeamResponse {
+    team: TeamDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct TeamDetail {
+    created_by: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MembersResponse {
+    #[serde(default)]
+    members: Vec<TeamMemberInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RolesResponse {
+    #[serde(default)]
+    roles: Vec<TeamRoleInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MyPermissionsResponse {
+    #[serde(default)]
+    permissions: Vec<String>,
+}
+
+fn cache_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    config_store::config_path(app, CACHE_FILE)
+}
+
+fn current_member_id(app: &tauri::AppHandle) -> Option<String> {
+    account::load_session(app)
+        .member_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// The cache of the signed-in member; another account's cache reads as empty.
+fn load_cache(app: &tauri::AppHandle) -> TeamCache {
+    let member_id = current_member_id(app);
+    let cache = cache_path(app)
+        .ok()
+        .and_then(|path| config_store::read::<TeamCache>(&path, &migrations::TEAM_CACHE))
+        .unwrap_or_default();
+    if cache.member_id.is_some() && cache.member_id == member_id {
+        cache
+    } else {
+        TeamCache {
+            member_id,
+            ..TeamCache::default()
+        }
+    }
+}
+
+fn update_cache(app: &tauri::AppHandle, f: impl FnOnce(&mut TeamCache)) -> AppResult<()> {
+    let path = cache_path(app).map_err(AppError::storage)?;
+    config_store::with_lock(&path, || {
+        let mut cache = load_cache(app);
+        f(&mut cache);
+        // Resolved again in case the data directory moved while waiting.
+        let path = cache_path(app).map_err(AppError::storage)?;
+        config_store::write(&path, &migrations::TEAM_CACHE, &cache).map_err(AppError::storage)
+    })?
+}
+
+/// Remove the cache. Used on logout.
+pub fn clear_cache(app: &tauri::AppHandle) -> Result<(), String> {
+    config_store::remove(&cache_path(app)?)
+}
+
+pub async fn fetch(client: &ApiClient, team_id: i64, member_id: i64) -> Result<TeamSnapshot, ApiError> {
+    let team: TeamResponse = client.get(&format!("/api/teams/{}", team_id)).await?;
+    let members: MembersResponse = client.get(&format!("/api/teams/{}/members", team_id)).await?;
+    let roles: RolesResponse = client.get(&format!("/api/teams/{}/roles", team_id)).await?;
+    let permissions: MyPermissionsResponse = client
+        .get(&format!("/api/teams/{}/permissions/me", team_id))
+        .await?;
+    let role_id = members
+        .members
+        .iter()
+        .find(|m| m.member_id == member_id)
+        .and_then(|m| m.role_id);
+
+    Ok(TeamSnapshot {
+        team_id,
+        owner_member_id: Some(team.team.created_by),
+        members: members.members,
+        roles: roles.roles,
+        role_id,
+        permissions: permissions.permissions,
+        fetched_at_unix: now_unix(),
+    })
+}
+
+fn team_id_for(app: &tauri::AppHandle, workspace_id: &str) -> AppResult<Option<i64>> {
+    let ws = workspace::load_state(app)
+        .workspaces
+        .into_iter()
+        .find(|ws| ws.id == workspace_id)
+        .ok_or_else(|| workspace::workspace_not_found(workspace_id))?;
+    if ws.workspace_type == WorkspaceType::Personal {
+        return Ok(None);
+    }
+    ws.team_id.map(Some).ok_or_else(|| {
+        AppError::conflict("team_not_linked", "Team workspace has no server team yet")
+            .with_details(serde_json::json!({ "workspace_id": workspace_id }))
+    })
+}
+
+fn team_not_found(team_id: i64) -> AppError {
+    AppError::not_found("team_not_found", "Team not found or no longer accessible")
+        .with_details(serde_json::json!({ "team_id": team_id }))
+}
+
+/// Cached access to a team, refreshed when older than the TTL or `force` is
+/// set. Losing membership on the server drops the entry.
+pub async fn team_access(app: &tauri::AppHandle, team_id: i64, force: bool) -> AppResult<TeamAccess> {
+    let member_id = current_member_id(app).ok_or_else(|| AppError::from(ApiError::Unauthorized))?;
+    let member_id_num: i64 = member_id
+        .parse()
+        .map_err(|_| AppError::auth("invalid_session", "Stored member id is not numeric"))?;
+    let cached = load_cache(app).teams.remove(&team_id);
+    let access = |snapshot: TeamSnapshot, stale: bool| TeamAccess {
+        is_owner: snapshot.owner_member_id == Some(member_id_num),
+        snapshot,
+        stale,
+    };
+
+    if let Some(snapshot) = &cached {
+        if !force && now_unix() - snapshot.fetched_at_unix < TEAM_CACHE_TTL_SECS {
+            return Ok(access(snapshot.clone(), false));
+        }
+    }
+
+    let client = ApiClient::from_app(app);
+    match fetch(&client, team_id, member_id_num).await {
+        Ok(snapshot) => {
+            let stored = snapshot.clone();
+            update_cache(app, |cache| {
+                cache.teams.insert(team_id, stored);
+            })?;
+            Ok(access(snapshot, false))
+        }
+        Err(ApiError::Offline(e)) => match cached {
+            Some(snapshot) => {
+                log::warn!("team cache: refresh of team {} failed, using cache: {}", team_id, e);
+                Ok(access(snapshot, true))
+            }
+            None => Err(ApiError::Offline(e).into()),
+        },
+        Err(ApiError::Status(403 | 404)) => {
+            update_cache(app, |cache| {
+                cache.teams.remove(&team_id);
+            })?;
+            Err(team_not_found(team_id))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Drop cached teams no workspace refers to any more.
+fn on_workspace_changed(app: &tauri::AppHandle, change: &WorkspaceChange) {
+    if !matches!(change, WorkspaceChange::Deleted(_)) {
+        return;
+    }
+    let state = workspace::load_state(app);
+    let result = update_cache(app, |cache| {
+        cache
+            .teams
+            .retain(|team_id, _| state.workspaces.iter().any(|ws| ws.team_id == Some(*team_id)));
+    });
+    if let Err(e) = result {
+        log::warn!("team cache: failed to prune: {}", e);
+    }
+}
+
+pub fn subscribe_to_workspaces(app: &tauri::AppHandle) {
+    workspace::subscribe(app, Box::new(on_workspace_changed));
+}
+
+#[tauri::command]
+pub async fn get_team_access(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    force_refresh: Option<bool>,
+) -> AppResult<Option<TeamAccess>> {
+    match team_id_for(&app, workspace_id.trim())? {
+        Some(team_id) => Ok(Some(team_access(&app, team_id, force_refresh.unwrap_or(false)).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Whether the signed-in member may do `code` in the workspace. Personal
+/// workspaces allow everything.
+#[tauri::command]
+pub async fn has_permission(app: tauri::AppHandle, workspace_id: String, code: String) -> AppResult<bool> {
+    let code = code.trim().to_uppercase();
+    if !PERMISSION_CODES.contains(&code.as_str()) {
+        return Err(AppError::validation("unknown_permission", "Unknown permission code")
+            .with_details(serde_json::json!({ "code": code, "allowed": PERMISSION_CODES })));
+    }
+    let Some(team_id) = team_id_for(&app, workspace_id.trim())? else {
+        return Ok(true);
+    };
+    match team_access(&app, team_id, false).await {
+        Ok(access) => Ok(access.is_owner || access.snapshot.permissions.iter().any(|p| p == &code)),
+        Err(e) if e.code == "team_not_found" => Ok(false),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,283 @@
+/// Timezone-aware time helpers shared by backend modules.
+///
+/// Everything is stored as unix seconds; these helpers convert to and from
+/// wall-clock time in the user's IANA timezone (`UserPreferences.timezone`).
+/// Local times that fall into a DST gap resolve to the first valid instant
+/// after the gap, and repeated (fold) times resolve to the earlier instant.
+use chrono::{DateTime, Datelike, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use chrono_tz::Tz;
+
+use crate::holidays;
+use crate::preferences::WorkingHours;
+
+const FALLBACK_TIMEZONE: Tz = chrono_tz::Asia::Seoul;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+/// Longest DST gap in the tz database is well under this.
+const MAX_GAP_MINUTES: i64 = 3 * 60;
+
+pub fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Parse an IANA name, falling back to Asia/Seoul for unknown values.
+pub fn parse_timezone(name: &str) -> Tz {
+    name.trim().parse().unwrap_or_else(|_| {
+        log::warn!("time: unknown timezone {:?}, using {}", name, FALLBACK_TIMEZONE);
+        FALLBACK_TIMEZONE
+    })
+}
+
+pub fn local_datetime(unix: i64, tz: Tz) -> DateTime<Tz> {
+    tz.timestamp_opt(unix, 0)
+        .earliest()
+        .unwrap_or_else(|| tz.timestamp_opt(0, 0).unwrap())
+}
+
+pub fn local_date(unix: i64, tz: Tz) -> NaiveDate {
+    local_datetime(unix, tz).date_naive()
+}
+
+/// Minutes since local midnight.
+pub fn local_minute_of_day(unix: i64, tz: Tz) -> u32 {
+    let local = local_datetime(unix, tz);
+    local.hour() * 60 + local.minute()
+}
+
+/// Weekday number, 0 = Sunday (same convention as `WorkingHours.days`).
+pub fn local_weekday(date: NaiveDate) -> u8 {
+    date.weekday().num_days_from_sunday() as u8
+}
+
+/// Unix time of a wall-clock time on a local date.
+pub fn local_time_to_unix(date: NaiveDate, minute_of_day: u32, tz: Tz) -> i64 {
+    let minute_of_day = minute_of_day.min(MINUTES_PER_DAY - 1);
+    let naive = date
+        .and_hms_opt(minute_of_day / 60, minute_of_day % 60, 0)
+        .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).unwrap());
+    resolve_local(naive, tz)
+}
+
+fn resolve_local(naive: NaiveDateTime, tz: Tz) -> i64 {
+    for shift in 0..=MAX_GAP_MINUTES {
+        let candidate = naive + chrono::Duration::minutes(shift);
+        match tz.from_local_datetime(&candidate) {
+            LocalResult::Single(dt) => return dt.timestamp(),
+            LocalResult::Ambiguous(earliest, _) => return earliest.timestamp(),
+            LocalResult::None => continue,
+        }
+    }
+    // Unreachable with real tz data; treat the wall-clock time as UTC.
+    naive.and_utc().timestamp()
+}
+
+/// Parse a timestamp from the API: RFC 3339, or a naive
+/// `YYYY-MM-DD HH:MM:SS` (DATETIME columns are returned as is) read as
+/// wall-clock time in `tz`.
+pub fn parse_server_datetime(value: &str, tz: Tz) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.timestamp());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|naive| resolve_local(naive, tz))
+}
+
+pub fn start_of_local_day(date: NaiveDate, tz: Tz) -> i64 {
+    local_time_to_unix(date, 0, tz)
+}
+
+/// First occurrence of `minute_of_day` local time strictly after `after_unix`.
+/// Computed on calendar dates, so it stays at the same wall-clock time across
+/// DST changes instead of drifting by an hour.
+pub fn next_local_time(after_unix: i64, minute_of_day: u32, tz: Tz) -> i64 {
+    let mut date = local_date(after_unix, tz);
+    loop {
+        let candidate = local_time_to_unix(date, minute_of_day, tz);
+        if candidate > after_unix {
+            return candidate;
+        }
+        date = date.succ_opt().unwrap_or(date);
+    }
+}
+
+/// Whether `unix` falls into the daily window `[start, end)`. Windows that
+/// wrap past midnight (e.g. 22:00–07:00) are supported.
+pub fn is_in_daily_window(unix: i64, tz: Tz, start: u32, end: u32) -> bool {
+    let minute = local_minute_of_day(unix, tz);
+    if start <= end {
+        minute >= start && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+pub fn is_working_day(date: NaiveDate, hours: &WorkingHours) -> bool {
+    hours.days.contains(&local_weekday(date))
+        && !(hours.exclude_holidays && holidays::is_holiday(date))
+}
+
+/// First working day on or after `date`. Gives up after a year so an empty
+/// `days` list cannot loop forever.
+pub fn next_working_day(date: NaiveDate, hours: &WorkingHours) -> Option<NaiveDate> {
+    date.iter_days()
+        .take(366)
+        .find(|day| is_working_day(*day, hours))
+}
+
+/// `YYYY-MM-DD` in the given timezone.
+pub fn local_date_string(unix: i64, tz: Tz) -> String {
+    local_date(unix, tz).format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    const NEW_YORK: Tz = chrono_tz::America::New_York;
+    const SEOUL: Tz = chrono_tz::Asia::Seoul;
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> i64 {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0)
+            .unwrap()
+            .timestamp()
+    }
+
+    fn day(y: i32, mo: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, mo, d).unwrap()
+    }
+
+    #[test]
+    fn spring_forward_gap_resolves_after_the_gap() {
+        // 02:30 does not exist on 2025-03-09; clocks jump to 03:00 EDT.
+        assert_eq!(
+            local_time_to_unix(day(2025, 3, 9), 2 * 60 + 30, NEW_YORK),
+            utc(2025, 3, 9, 7, 0)
+        );
+    }
+
+    #[test]
+    fn fall_back_overlap_resolves_to_the_earlier_instant() {
+        // 01:30 happens twice on 2025-11-02; the EDT one comes first.
+        assert_eq!(
+            local_time_to_unix(day(2025, 11, 2), 60 + 30, NEW_YORK),
+            utc(2025, 11, 2, 5, 30)
+        );
+    }
+
+    #[test]
+    fn next_local_time_keeps_wall_clock_across_dst() {
+        let nine = 9 * 60;
+        // 09:00 EST on the 8th, then 09:00 EDT on the 9th: 23 hours later.
+        assert_eq!(
+            next_local_time(utc(2025, 3, 8, 13, 0), nine, NEW_YORK),
+            utc(2025, 3, 8, 14, 0)
+        );
+        assert_eq!(
+            next_local_time(utc(2025, 3, 8, 14, 0), nine, NEW_YORK),
+            utc(2025, 3, 9, 13, 0)
+        );
+        // Back to EST: 25 hours later.
+        assert_eq!(
+            next_local_time(utc(2025, 11, 1, 13, 0), nine, NEW_YORK),
+            utc(2025, 11, 2, 14, 0)
+        );
+    }
+
+    #[test]
+    fn next_local_time_into_a_gap() {
+        assert_eq!(
+            next_local_time(utc(2025, 3, 8, 8, 0), 2 * 60 + 30, NEW_YORK),
+            utc(2025, 3, 9, 7, 0)
+        );
+    }
+
+    #[test]
+    fn quiet_hours_across_midnight() {
+        let (start, end) = (22 * 60, 7 * 60);
+        // 23:30 and 06:59 KST are inside, 07:00 and 12:00 are not.
+        assert!(is_in_daily_window(
+            utc(2025, 6, 1, 14, 30),
+            SEOUL,
+            start,
+            end
+        ));
+        assert!(is_in_daily_window(
+            utc(2025, 6, 1, 21, 59),
+            SEOUL,
+            start,
+            end
+        ));
+        assert!(!is_in_daily_window(
+            utc(2025, 6, 1, 22, 0),
+            SEOUL,
+            start,
+            end
+        ));
+        assert!(!is_in_daily_window(
+            utc(2025, 6, 1, 3, 0),
+            SEOUL,
+            start,
+            end
+        ));
+        // The window ends the next morning when entered before midnight,
+        // the same morning when entered after it.
+        assert_eq!(
+            next_local_time(utc(2025, 6, 1, 14, 30), end, SEOUL),
+            utc(2025, 6, 1, 22, 0)
+        );
+        assert_eq!(
+            next_local_time(utc(2025, 6, 1, 16, 0), end, SEOUL),
+            utc(2025, 6, 1, 22, 0)
+        );
+    }
+
+    #[test]
+    fn quiet_hours_across_midnight_and_dst() {
+        // 23:00–07:00 over the fall-back night is one hour longer.
+        let (start, end) = (23 * 60, 7 * 60);
+        let entered = utc(2025, 11, 2, 3, 0); // 23:00 EDT on the 1st
+        assert!(is_in_daily_window(entered, NEW_YORK, start, end));
+        assert_eq!(
+            next_local_time(entered, end, NEW_YORK),
+            utc(2025, 11, 2, 12, 0)
+        );
+    }
+
+    #[test]
+    fn server_datetimes() {
+        assert_eq!(
+            parse_server_datetime("2025-01-02 09:00:00", SEOUL),
+            Some(utc(2025, 1, 2, 0, 0))
+        );
+        assert_eq!(
+            parse_server_datetime("2025-01-02T09:00:00+09:00", NEW_YORK),
+            Some(utc(2025, 1, 2, 0, 0))
+        );
+        assert_eq!(
+            parse_server_datetime("2025-03-09 02:30:00", NEW_YORK),
+            Some(utc(2025, 3, 9, 7, 0))
+        );
+        assert_eq!(parse_server_datetime("yesterday", SEOUL), None);
+    }
+
+    #[test]
+    fn daytime_window() {
+        assert!(is_in_daily_window(
+            utc(2025, 6, 1, 0, 0),
+            SEOUL,
+            9 * 60,
+            18 * 60
+        ));
+        assert!(!is_in_daily_window(
+            utc(2025, 6, 1, 9, 0),
+            SEOUL,
+            9 * 60,
+            18 * 60
+        ));
+    }
+}
@@ -1,10 +1,21 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
-use tauri::Manager;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
 
-const WORKSPACE_STATE_FILE: &str = "workspace_state.json";
+use crate::config_store;
+use crate::entitlements::{self, Entitlements};
+use crate::error::{AppError, AppResult};
+use crate::migrations;
+use crate::preferences;
+use crate::time::now_unix;
+use crate::workspace_session::{self, WorkspaceSession};
+use crate::workspace_template;
+
+const WORKSPACE_STATE_FILE: &str = migrations::WORKSPACE_STATE.file;
+const MAX_WORKSPACE_NAME_LEN: usize = 50;
+const MAX_WORKSPACE_ICON_LEN: usize = 16;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -24,14 +35,223 @@ pub struct Workspace {
     pub team_id: Option<i64>,
     pub created_at_unix: i64,
     pub updated_at_unix: i64,
+    /// Server `workspace_id`; `None` until the workspace exists on the server.
+    #[serde(default)]
+    pub server_id: Option<i64>,
+    /// Name as last seen on the server, the base for detecting renames.
+    #[serde(default)]
+    pub synced_name: Option<String>,
+    /// Hidden from the default lists; its data is kept and alarms paused.
+    #[serde(default)]
+    pub archived_at_unix: Option<i64>,
+    /// Moved to the trash; purged `trash_retention_days` (a preference) later.
+    #[serde(default)]
+    pub trashed_at_unix: Option<i64>,
+    /// Position in the switcher, after pinned workspaces. Ties keep insertion order.
+    #[serde(default)]
+    pub sort_order: i64,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub appearance: WorkspaceAppearance,
+    /// Template whose tags and tasks are still to be created on the server;
+    /// cleared by workspace sync once applied.
+    #[serde(default)]
+    pub pending_template_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Per-workspace look. `None` falls back to the user preferences.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkspaceAppearance {
+    /// `#RRGGBB`.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// An emoji or an icon name.
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub default_calendar_view: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WorkspaceState {
     #[serde(default)]
     pub current_workspace_id: Option<String>,
     #[serde(default)]
     pub workspaces: Vec<Workspace>,
+    #[serde(default)]
+    pub last_synced_at_unix: Option<i64>,
+    /// Server workspaces deleted locally. They are not pulled again; deletion
+    /// itself is never pushed to the server.
+    #[serde(default)]
+    pub removed_server_ids: Vec<i64>,
+}
+
+/// How other modules refer to a workspace. Tasks and alarms come from the
+/// server and carry its numeric `workspace_id`; local state uses `Workspace.id`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct WorkspaceIdentity {
+    pub local_id: String,
+    pub server_id: Option<i64>,
+    pub name: String,
+}
+
+impl Workspace {
+    pub fn identity(&self) -> WorkspaceIdentity {
+        WorkspaceIdentity {
+            local_id: self.id.clone(),
+            server_id: self.server_id,
+            name: self.name.clone(),
+        }
+    }
+
+    /// Neither archived nor in the trash.
+    pub fn is_active(&self) -> bool {
+        self.archived_at_unix.is_none() && self.trashed_at_unix.is_none()
+    }
+
+    pub fn is_trashed(&self) -> bool {
+        self.trashed_at_unix.is_some()
+    }
+
+    pub fn trash_expires_at_unix(&self, retention_days: i64) -> Option<i64> {
+        self.trashed_at_unix
+            .map(|at| at + retention_days * 24 * 60 * 60)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedWorkspace {
+    pub workspace: Workspace,
+    pub expires_at_unix: i64,
+}
+
+/// Mapping table between local ids and server ids.
+#[derive(Debug, Default)]
+pub struct WorkspaceIdMap {
+    by_local: HashMap<String, WorkspaceIdentity>,
+    by_server: HashMap<i64, String>,
+    inactive: HashSet<String>,
+}
+
+impl WorkspaceIdMap {
+    pub fn new(state: &WorkspaceState) -> Self {
+        let mut map = WorkspaceIdMap::default();
+        for ws in &state.workspaces {
+            if let Some(server_id) = ws.server_id {
+                map.by_server.insert(server_id, ws.id.clone());
+            }
+            if !ws.is_active() {
+                map.inactive.insert(ws.id.clone());
+            }
+            map.by_local.insert(ws.id.clone(), ws.identity());
+        }
+        map
+    }
+
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        Self::new(&load_state(app))
+    }
+
+    pub fn by_local_id(&self, local_id: &str) -> Option<&WorkspaceIdentity> {
+        self.by_local.get(local_id)
+    }
+
+    pub fn by_server_id(&self, server_id: i64) -> Option<&WorkspaceIdentity> {
+        self.by_server.get(&server_id).and_then(|id| self.by_local.get(id))
+    }
+
+    /// False for archived and trashed workspaces.
+    pub fn is_active(&self, local_id: &str) -> bool {
+        !self.inactive.contains(local_id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WorkspaceChange {
+    /// Created locally or added by sync.
+    Created(WorkspaceIdentity),
+    Renamed(WorkspaceIdentity),
+    /// Purged for good, or removed by sync.
+    Deleted(WorkspaceIdentity),
+    /// Archived or moved to the trash.
+    Deactivated(WorkspaceIdentity),
+    /// Unarchived or restored from the trash.
+    Reactivated(WorkspaceIdentity),
+    Switched {
+        from: Option<WorkspaceIdentity>,
+        to: WorkspaceIdentity,
+    },
+}
+
+pub type WorkspaceSubscriber = Box<dyn Fn(&tauri::AppHandle, &WorkspaceChange) + Send + Sync>;
+
+/// Lifecycle hooks. Modules holding per-workspace data (alarms, caches,
+/// data directories, ...) register here to follow creation, renames,
+/// archiving, deletions and switches. Subscribers run in registration order.
+#[derive(Default)]
+pub struct WorkspaceSubscribers(Mutex<Vec<WorkspaceSubscriber>>);
+
+pub fn subscribe(app: &tauri::AppHandle, subscriber: WorkspaceSubscriber) {
+    if let Some(subscribers) = app.try_state::<WorkspaceSubscribers>() {
+        if let Ok(mut list) = subscribers.0.lock() {
+            list.push(subscriber);
+        }
+    }
+}
+
+/// Removing the current workspace hands over to whichever workspace
+/// `ensure_valid_state` picked; report that as a switch.
+pub fn notify_current_replaced(app: &tauri::AppHandle, removed: &WorkspaceIdentity, state: &WorkspaceState) {
+    let current = state
+        .current_workspace_id
+        .as_deref()
+        .and_then(|id| state.workspaces.iter().find(|ws| ws.id == id));
+    if let Some(current) = current {
+        notify(
+            app,
+            WorkspaceChange::Switched {
+                from: Some(removed.clone()),
+                to: current.identity(),
+            },
+        );
+    }
+}
+
+/// Payload of `workspace://switched`, sent to every window.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceSwitchedPayload {
+    pub previous_workspace_id: Option<String>,
+    pub previous_server_id: Option<i64>,
+    pub workspace_id: String,
+    pub server_id: Option<i64>,
+    /// Where to put the UI back; `None` the first time the workspace is opened.
+    pub session: Option<WorkspaceSession>,
+}
+
+/// Call after the state has been saved and the workspace lock released.
+/// Switches are also broadcast as `workspace://switched` once the backend
+/// subscribers have caught up.
+pub fn notify(app: &tauri::AppHandle, change: WorkspaceChange) {
+    if let Some(subscribers) = app.try_state::<WorkspaceSubscribers>() {
+        if let Ok(list) = subscribers.0.lock() {
+            for subscriber in list.iter() {
+                subscriber(app, &change);
+            }
+        }
+    }
+    if let WorkspaceChange::Switched { from, to } = change {
+        let payload = WorkspaceSwitchedPayload {
+            previous_workspace_id: from.as_ref().map(|w| w.local_id.clone()),
+            previous_server_id: from.and_then(|w| w.server_id),
+            session: workspace_session::session_for(app, &to.local_id),
+            workspace_id: to.local_id,
+            server_id: to.server_id,
+        };
+        if let Err(e) = app.emit("workspace://switched", payload) {
+            log::warn!("workspace: failed to emit switch event: {}", e);
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -49,167 +269,339 @@ pub struct TeamWorkspaceCreationResult {
     pub workspace: Option<Workspace>,
 }
 
-fn now_unix() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0)
-}
+/// Name of the workspace created on first launch.
+pub const DEFAULT_WORKSPACE_NAME: &str = "내 워크스페이스";
 
-fn new_team_id() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as i64)
-        .unwrap_or_else(|_| now_unix())
+/// Local id, independent of the server's numeric `workspace_id`.
+pub fn new_workspace_id() -> String {
+    format!("ws_{}", uuid::Uuid::new_v4())
 }
 
-fn new_workspace_id() -> String {
-    let ts = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis())
-        .unwrap_or(0);
-    format!("ws_{}", ts)
-}
-
-fn normalize_plan(plan_tier: &str) -> String {
-    let normalized = plan_tier.trim().to_lowercase();
-    if normalized.is_empty() {
-        "free".to_string()
-    } else {
-        normalized
-    }
+/// Workspaces counted against the plan: active ones. Archived and trashed
+/// workspaces are checked again when they are reactivated.
+fn count_of_type(state: &WorkspaceState, workspace_type: &WorkspaceType) -> usize {
+    state
+        .workspaces
+        .iter()
+        .filter(|ws| &ws.workspace_type == workspace_type && ws.is_active())
+        .count()
 }
 
-fn is_paid_plan(plan_tier: &str) -> bool {
-    matches!(
-        normalize_plan(plan_tier).as_str(),
-        "pro" | "plus" | "team" | "business" | "enterprise" | "paid" | "premium"
-    )
+fn active_personal_count(state: &WorkspaceState) -> usize {
+    state
+        .workspaces
+        .iter()
+        .filter(|ws| ws.workspace_type == WorkspaceType::Personal && ws.is_active())
+        .count()
 }
 
-fn validate_workspace_name(name: &str) -> Result<String, String> {
+fn validate_workspace_name(name: &str) -> AppResult<String> {
     let trimmed = name.trim();
     let len = trimmed.chars().count();
     if len == 0 {
-        return Err("Workspace name is required".to_string());
+        return Err(AppError::validation(
+            "workspace_name_required",
+            "Workspace name is required",
+        ));
     }
-    if len > 50 {
-        return Err("Workspace name must be 50 characters or less".to_string());
+    if len > MAX_WORKSPACE_NAME_LEN {
+        return Err(AppError::validation(
+            "workspace_name_too_long",
+            format!("Workspace name must be {} characters or less", MAX_WORKSPACE_NAME_LEN),
+        )
+        .with_details(serde_json::json!({ "max": MAX_WORKSPACE_NAME_LEN })));
     }
     Ok(trimmed.to_string())
 }
 
-fn workspace_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    app.path()
-        .app_config_dir()
-        .map(|dir| dir.join(WORKSPACE_STATE_FILE))
-        .map_err(|e| format!("Failed to resolve app config dir: {}", e))
+fn validate_appearance(appearance: &WorkspaceAppearance) -> AppResult<WorkspaceAppearance> {
+    let color = match appearance.color.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        Some(color) => Some(preferences::normalize_hex_color(color).ok_or_else(|| {
+            AppError::validation(
+                "workspace_color_invalid",
+                "Workspace color must be a hex color such as #3B82F6",
+            )
+            .with_details(serde_json::json!({ "color": color }))
+        })?),
+        None => None,
+    };
+
+    let icon = appearance
+        .icon
+        .as_deref()
+        .map(str::trim)
+        .filter(|i| !i.is_empty());
+    if let Some(icon) = icon {
+        if icon.chars().count() > MAX_WORKSPACE_ICON_LEN {
+            return Err(AppError::validation(
+                "workspace_icon_too_long",
+                format!("Workspace icon must be {} characters or less", MAX_WORKSPACE_ICON_LEN),
+            )
+            .with_details(serde_json::json!({ "max": MAX_WORKSPACE_ICON_LEN })));
+        }
+        if icon.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(AppError::validation(
+                "workspace_icon_invalid",
+                "Workspace icon must be an emoji or an icon name",
+            ));
+        }
+    }
+
+    let view = appearance
+        .default_calendar_view
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    if let Some(view) = view {
+        if !preferences::CALENDAR_VIEWS.contains(&view) {
+            return Err(AppError::validation(
+                "workspace_view_unsupported",
+                format!("Calendar view must be one of: {}", preferences::CALENDAR_VIEWS.join(", ")),
+            )
+            .with_details(serde_json::json!({ "allowed": preferences::CALENDAR_VIEWS })));
+        }
+    }
+
+    Ok(WorkspaceAppearance {
+        color,
+        icon: icon.map(str::to_string),
+        default_calendar_view: view.map(str::to_string),
+    })
 }
 
-fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
-    let raw = fs::read_to_string(path).ok()?;
-    serde_json::from_str(&raw).ok()
+/// `sort_order` for a workspace appended to the end of the switcher.
+pub fn next_sort_order(state: &WorkspaceState) -> i64 {
+    state
+        .workspaces
+        .iter()
+        .map(|ws| ws.sort_order + 1)
+        .max()
+        .unwrap_or(0)
 }
 
-fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+/// Pinned first, then by `sort_order`.
+fn sort_for_display(workspaces: &mut [Workspace]) {
+    workspaces.sort_by_key(|ws| (!ws.pinned, ws.sort_order));
+}
+
+fn require_workspace_id(workspace_id: &str) -> AppResult<&str> {
+    let workspace_id = workspace_id.trim();
+    if workspace_id.is_empty() {
+        return Err(AppError::validation(
+            "workspace_id_required",
+            "workspace_id is required",
+        ));
     }
-    let json = serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize json: {}", e))?;
-    fs::write(path, json).map_err(|e| format!("Failed to write file: {}", e))
+    Ok(workspace_id)
+}
+
+pub fn workspace_not_found(workspace_id: &str) -> AppError {
+    AppError::not_found("workspace_not_found", "Workspace not found")
+        .with_details(serde_json::json!({ "workspace_id": workspace_id }))
+}
+
+fn workspace_inactive(workspace_id: &str) -> AppError {
+    AppError::conflict("workspace_inactive", "Workspace is archived or in the trash")
+        .with_details(serde_json::json!({ "workspace_id": workspace_id }))
+}
+
+fn workspace_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    config_store::config_path(app, WORKSPACE_STATE_FILE)
 }
 
 fn default_personal_workspace() -> Workspace {
     let now = now_unix();
     Workspace {
         id: new_workspace_id(),
-        name: "내 워크스페이스".to_string(),
+        name: DEFAULT_WORKSPACE_NAME.to_string(),
         workspace_type: WorkspaceType::Personal,
         plan_tier: None,
         team_id: None,
         created_at_unix: now,
         updated_at_unix: now,
+        server_id: None,
+        synced_name: None,
+        archived_at_unix: None,
+        trashed_at_unix: None,
+        sort_order: 0,
+        pinned: false,
+        appearance: WorkspaceAppearance::default(),
+        pending_template_id: None,
     }
 }
 
+/// The current workspace is always an active one; archived and trashed
+/// workspaces alone do not count.
 fn ensure_valid_state(mut state: WorkspaceState) -> WorkspaceState {
-    if state.workspaces.is_empty() {
+    if !state.workspaces.iter().any(Workspace::is_active) {
         let ws = default_personal_workspace();
         state.current_workspace_id = Some(ws.id.clone());
         state.workspaces.push(ws);
         return state;
     }
 
-    let current_exists = state
+    let current_active = state
         .current_workspace_id
         .as_ref()
-        .map(|id| state.workspaces.iter().any(|ws| &ws.id == id))
+        .map(|id| state.workspaces.iter().any(|ws| &ws.id == id && ws.is_active()))
         .unwrap_or(false);
 
-    if !current_exists {
-        state.current_workspace_id = state.workspaces.first().map(|ws| ws.id.clone());
+    if !current_active {
+        state.current_workspace_id = state
+            .workspaces
+            .iter()
+            .find(|ws| ws.is_active())
+            .map(|ws| ws.id.clone());
     }
 
     state
 }
 
-fn load_state(app: &tauri::AppHandle) -> WorkspaceState {
+pub fn load_state(app: &tauri::AppHandle) -> WorkspaceState {
     let path = match workspace_state_path(app) {
         Ok(path) => path,
         Err(_) => {
-            return ensure_valid_state(WorkspaceState {
-                current_workspace_id: None,
-                workspaces: Vec::new(),
-            });
+            return ensure_valid_state(WorkspaceState::default());
         }
     };
 
-    let state = read_json::<WorkspaceState>(&path).unwrap_or(WorkspaceState {
-        current_workspace_id: None,
-        workspaces: Vec::new(),
-    });
+    let state = config_store::read::<WorkspaceState>(&path, &migrations::WORKSPACE_STATE).unwrap_or_default();
     ensure_valid_state(state)
 }
 
+/// The state as stored, or `None` when there is no readable file and
+/// `load_state` would hand out a fresh default.
+pub fn read_stored_state(app: &tauri::AppHandle) -> Option<WorkspaceState> {
+    let path = workspace_state_path(app).ok()?;
+    config_store::read::<WorkspaceState>(&path, &migrations::WORKSPACE_STATE)
+}
+
 fn save_state(app: &tauri::AppHandle, state: &WorkspaceState) -> Result<(), String> {
     let path = workspace_state_path(app)?;
-    write_json(&path, state)
+    config_store::write(&path, &migrations::WORKSPACE_STATE, state)
 }
 
-fn update_state(
+/// Remove `workspace_state.json`; the next load recreates a default personal
+/// workspace. Used on logout.
+pub fn clear_local_state(app: &tauri::AppHandle) -> Result<(), String> {
+    config_store::remove(&workspace_state_path(app)?)
+}
+
+pub fn update_state(
     app: &tauri::AppHandle,
-    f: impl FnOnce(&mut WorkspaceState) -> Result<(), String>,
-) -> Result<WorkspaceState, String> {
-    let mut state = load_state(app);
-    f(&mut state)?;
-    let state = ensure_valid_state(state);
-    save_state(app, &state)?;
+    f: impl FnOnce(&mut WorkspaceState) -> AppResult<()>,
+) -> AppResult<WorkspaceState> {
+    let path = workspace_state_path(app).map_err(AppError::storage)?;
+    config_store::with_lock(&path, || {
+        let mut state = load_state(app);
+        f(&mut state)?;
+        let state = ensure_valid_state(state);
+        save_state(app, &state).map_err(AppError::storage)?;
+        Ok(state)
+    })?
+}
+
+/// Validate every workspace name and replace the stored state, then notify
+/// subscribers of the difference. Used by settings import and backup restore.
+pub fn replace_state(app: &tauri::AppHandle, incoming: WorkspaceState) -> AppResult<WorkspaceState> {
+    let incoming = validate_state(incoming)?;
+    let mut previous = WorkspaceState::default();
+    let state = update_state(app, |state| {
+        previous = std::mem::replace(state, incoming);
+        Ok(())
+    })?;
+    for change in changes_between(&previous, &state) {
+        notify(app, change);
+    }
     Ok(state)
 }
 
-#[tauri::command]
-pub fn get_workspace_state(app: tauri::AppHandle) -> WorkspaceState {
-    load_state(&app)
+/// Lifecycle changes that turn `before` into `after`, in the order sync
+/// reports them: creations, renames, (de)activations, deletions, then the
+/// switch of the current workspace.
+pub fn changes_between(before: &WorkspaceState, after: &WorkspaceState) -> Vec<WorkspaceChange> {
+    let find = |state: &WorkspaceState, id: &str| state.workspaces.iter().find(|ws| ws.id == id).cloned();
+    let mut changes = Vec::new();
+    for ws in &after.workspaces {
+        match find(before, &ws.id) {
+            None => changes.push(WorkspaceChange::Created(ws.identity())),
+            Some(old) => {
+                if old.name != ws.name {
+                    changes.push(WorkspaceChange::Renamed(ws.identity()));
+                }
+                if old.is_active() && !ws.is_active() {
+                    changes.push(WorkspaceChange::Deactivated(ws.identity()));
+                } else if !old.is_active() && ws.is_active() {
+                    changes.push(WorkspaceChange::Reactivated(ws.identity()));
+                }
+            }
+        }
+    }
+    for ws in &before.workspaces {
+        if find(after, &ws.id).is_none() {
+            changes.push(WorkspaceChange::Deleted(ws.identity()));
+        }
+    }
+    if before.current_workspace_id != after.current_workspace_id {
+        let from = before
+            .current_workspace_id
+            .as_deref()
+            .and_then(|id| find(before, id))
+            .map(|ws| ws.identity());
+        let to = after.current_workspace_id.as_deref().and_then(|id| find(after, id));
+        if let Some(to) = to {
+            changes.push(WorkspaceChange::Switched {
+                from,
+                to: to.identity(),
+            });
+        }
+    }
+    changes
+}
+
+pub fn validate_state(mut state: WorkspaceState) -> AppResult<WorkspaceState> {
+    let mut seen = std::collections::HashSet::new();
+    for ws in state.workspaces.iter_mut() {
+        if !seen.insert(ws.id.clone()) {
+            return Err(AppError::conflict(
+                "duplicate_workspace_id",
+                "Workspace ids must be unique",
+            )
+            .with_details(serde_json::json!({ "workspace_id": ws.id })));
+        }
+        ws.name = validate_workspace_name(&ws.name)?;
+        ws.appearance = validate_appearance(&ws.appearance)?;
+    }
+    Ok(ensure_valid_state(state))
 }
 
 #[tauri::command]
-pub fn list_personal_workspaces(app: tauri::AppHandle) -> Vec<Workspace> {
-    let state = load_state(&app);
+pub fn get_workspace_state(app: tauri::AppHandle) -> WorkspaceState {
+    let mut state = load_state(&app);
+    sort_for_display(&mut state.workspaces);
     state
+}
+
+/// Trashed workspaces are only listed by `list_workspace_trash`.
+fn list_of_type(app: &tauri::AppHandle, workspace_type: WorkspaceType, include_archived: bool) -> Vec<Workspace> {
+    let mut list: Vec<Workspace> = load_state(app)
         .workspaces
         .into_iter()
-        .filter(|ws| ws.workspace_type == WorkspaceType::Personal)
-        .collect()
+        .filter(|ws| ws.workspace_type == workspace_type && !ws.is_trashed())
+        .filter(|ws| include_archived || ws.archived_at_unix.is_none())
+        .collect();
+    sort_for_display(&mut list);
+    list
 }
 
 #[tauri::command]
-pub fn list_team_workspaces(app: tauri::AppHandle) -> Vec<Workspace> {
-    let state = load_state(&app);
-    state
-        .workspaces
-        .into_iter()
-        .filter(|ws| ws.workspace_type == WorkspaceType::Team)
-        .collect()
+pub fn list_personal_workspaces(app: tauri::AppHandle, include_archived: Option<bool>) -> Vec<Workspace> {
+    list_of_type(&app, WorkspaceType::Personal, include_archived.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn list_team_workspaces(app: tauri::AppHandle, include_archived: Option<bool>) -> Vec<Workspace> {
+    list_of_type(&app, WorkspaceType::Team, include_archived.unwrap_or(false))
 }
 
 #[tauri::command]
@@ -220,35 +612,78 @@ pub fn get_current_workspace(app: tauri::AppHandle) -> Option<Workspace> {
 }
 
 #[tauri::command]
-pub fn switch_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<Workspace, String> {
-    let workspace_id = workspace_id.trim();
-    if workspace_id.is_empty() {
-        return Err("workspace_id is required".to_string());
-    }
+pub fn switch_workspace(app: tauri::AppHandle, workspace_id: String) -> AppResult<Workspace> {
+    let workspace_id = require_workspace_id(&workspace_id)?;
 
+    let mut previous = None;
     let state = update_state(&app, |state| {
-        if state.workspaces.iter().any(|ws| ws.id == workspace_id) {
-            state.current_workspace_id = Some(workspace_id.to_string());
-            Ok(())
-        } else {
-            Err("Workspace not found".to_string())
+        let target = state
+            .workspaces
+            .iter()
+            .find(|ws| ws.id == workspace_id)
+            .ok_or_else(|| workspace_not_found(workspace_id))?;
+        if !target.is_active() {
+            return Err(workspace_inactive(workspace_id));
         }
+        previous = state.current_workspace_id.replace(workspace_id.to_string());
+        Ok(())
     })?;
 
-    let current_id = state.current_workspace_id.unwrap_or_default();
+    let map = WorkspaceIdMap::new(&state);
+    let current = map
+        .by_local_id(workspace_id)
+        .cloned()
+        .ok_or_else(|| workspace_not_found(workspace_id))?;
+    if previous.as_deref() != Some(workspace_id) {
+        notify(
+            &app,
+            WorkspaceChange::Switched {
+                from: previous.and_then(|id| map.by_local_id(&id).cloned()),
+                to: current,
+            },
+        );
+    }
+
     state
         .workspaces
         .into_iter()
-        .find(|ws| ws.id == current_id)
-        .ok_or_else(|| "Workspace not found".to_string())
+        .find(|ws| ws.id == workspace_id)
+        .ok_or_else(|| workspace_not_found(workspace_id))
+}
+
+/// A new workspace also becomes the current one; report both.
+fn notify_created(app: &tauri::AppHandle, state: &WorkspaceState, created: &Workspace, previous: Option<String>) {
+    let from = previous.and_then(|id| WorkspaceIdMap::new(state).by_local_id(&id).cloned());
+    notify(app, WorkspaceChange::Created(created.identity()));
+    notify(
+        app,
+        WorkspaceChange::Switched {
+            from,
+            to: created.identity(),
+        },
+    );
 }
 
 #[tauri::command]
-pub fn create_workspace(app: tauri::AppHandle, name: String) -> Result<Workspace, String> {
+pub fn create_workspace(
+    app: tauri::AppHandle,
+    name: String,
+    template_id: Option<String>,
+) -> AppResult<Workspace> {
     let name = validate_workspace_name(&name)?;
+    let template = template_id
+        .as_deref()
+        .map(|id| workspace_template::find_template(&app, id, &WorkspaceType::Personal))
+        .transpose()?;
+    let entitlements = entitlements::current(&app);
     let mut created: Option<Workspace> = None;
+    let mut previous = None;
 
-    update_state(&app, |state| {
+    let state = update_state(&app, |state| {
+        entitlements.check_workspace_limit(
+            &WorkspaceType::Personal,
+            count_of_type(state, &WorkspaceType::Personal),
+        )?;
         let now = now_unix();
         let workspace = Workspace {
             id: new_workspace_id(),
@@ -258,14 +693,28 @@ pub fn create_workspace(app: tauri::AppHandle, name: String) -> Result<Workspace
             team_id: None,
             created_at_unix: now,
             updated_at_unix: now,
+            server_id: None,
+            synced_name: None,
+            archived_at_unix: None,
+            trashed_at_unix: None,
+            sort_order: next_sort_order(state),
+            pinned: false,
+            appearance: template
+                .as_ref()
+                .map(|t| t.appearance.clone())
+                .unwrap_or_default(),
+            pending_template_id: template.as_ref().map(|t| t.id.clone()),
         };
-        state.current_workspace_id = Some(workspace.id.clone());
+        previous = state.current_workspace_id.replace(workspace.id.clone());
         state.workspaces.push(workspace.clone());
         created = Some(workspace);
         Ok(())
     })?;
+    if let Some(workspace) = &created {
+        notify_created(&app, &state, workspace, previous);
+    }
 
-    created.ok_or_else(|| "Failed to create workspace".to_string())
+    created.ok_or_else(|| AppError::io("workspace_create_failed", "Failed to create workspace"))
 }
 
 #[tauri::command]
@@ -273,7 +722,7 @@ pub fn rename_workspace(
     app: tauri::AppHandle,
     workspace_id: String,
     name: String,
-) -> Result<Workspace, String> {
+) -> AppResult<Workspace> {
     let name = validate_workspace_name(&name)?;
     let mut updated: Option<Workspace> = None;
 
@@ -282,67 +731,371 @@ pub fn rename_workspace(
             .workspaces
             .iter_mut()
             .find(|ws| ws.id == workspace_id)
-            .ok_or_else(|| "Workspace not found".to_string())?;
+            .ok_or_else(|| workspace_not_found(&workspace_id))?;
         ws.name = name.clone();
         ws.updated_at_unix = now_unix();
         updated = Some(ws.clone());
         Ok(())
     })?;
 
-    updated.ok_or_else(|| "Failed to rename workspace".to_string())
+    let updated =
+        updated.ok_or_else(|| AppError::io("workspace_rename_failed", "Failed to rename workspace"))?;
+    notify(&app, WorkspaceChange::Renamed(updated.identity()));
+    Ok(updated)
 }
 
+/// Put the given workspaces first, in that order; the rest keep their
+/// relative order after them.
 #[tauri::command]
-pub fn delete_workspace(app: tauri::AppHandle, workspace_id: String) -> Result<(), String> {
-    let workspace_id = workspace_id.trim();
-    if workspace_id.is_empty() {
-        return Err("workspace_id is required".to_string());
+pub fn reorder_workspaces(app: tauri::AppHandle, workspace_ids: Vec<String>) -> AppResult<Vec<Workspace>> {
+    let mut seen = HashSet::new();
+    for id in &workspace_ids {
+        if !seen.insert(id.as_str()) {
+            return Err(AppError::validation(
+                "duplicate_workspace_id",
+                "Each workspace may appear only once",
+            )
+            .with_details(serde_json::json!({ "workspace_id": id })));
+        }
     }
 
-    update_state(&app, |state| {
-        let target = state
-            .workspaces
+    let state = update_state(&app, |state| reorder_in(state, &workspace_ids))?;
+
+    let mut workspaces = state.workspaces;
+    sort_for_display(&mut workspaces);
+    Ok(workspaces)
+}
+
+/// State half of `reorder_workspaces`; `sort_order` is renumbered from 0.
+fn reorder_in(state: &mut WorkspaceState, workspace_ids: &[String]) -> AppResult<()> {
+    if let Some(missing) = workspace_ids
+        .iter()
+        .find(|id| !state.workspaces.iter().any(|ws| &ws.id == *id))
+    {
+        return Err(workspace_not_found(missing));
+    }
+    let mut ordered: Vec<Workspace> = std::mem::take(&mut state.workspaces);
+    ordered.sort_by_key(|ws| ws.sort_order);
+    ordered.sort_by_key(|ws| {
+        workspace_ids
             .iter()
+            .position(|id| id == &ws.id)
+            .unwrap_or(workspace_ids.len())
+    });
+    for (index, ws) in ordered.iter_mut().enumerate() {
+        ws.sort_order = index as i64;
+    }
+    state.workspaces = ordered;
+    Ok(())
+}
+
+fn update_workspace(
+    app: &tauri::AppHandle,
+    workspace_id: &str,
+    apply: impl FnOnce(&mut Workspace),
+) -> AppResult<Workspace> {
+    let workspace_id = require_workspace_id(workspace_id)?;
+    let mut updated = None;
+    update_state(app, |state| {
+        let ws = state
+            .workspaces
+            .iter_mut()
             .find(|ws| ws.id == workspace_id)
-            .cloned()
-            .ok_or_else(|| "Workspace not found".to_string())?;
-
-        if target.workspace_type == WorkspaceType::Personal {
-            let personal_count = state
-                .workspaces
-                .iter()
-                .filter(|ws| ws.workspace_type == WorkspaceType::Personal)
-                .count();
-            if personal_count <= 1 {
-                return Err("At least one personal workspace is required".to_string());
-            }
+            .ok_or_else(|| workspace_not_found(workspace_id))?;
+        apply(ws);
+        ws.updated_at_unix = now_unix();
+        updated = Some(ws.clone());
+        Ok(())
+    })?;
+    updated.ok_or_else(|| workspace_not_found(workspace_id))
+}
+
+#[tauri::command]
+pub fn set_workspace_pinned(app: tauri::AppHandle, workspace_id: String, pinned: bool) -> AppResult<Workspace> {
+    update_workspace(&app, &workspace_id, |ws| ws.pinned = pinned)
+}
+
+/// Replace the workspace's appearance; empty fields reset to the preference.
+#[tauri::command]
+pub fn update_workspace_appearance(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    appearance: WorkspaceAppearance,
+) -> AppResult<Workspace> {
+    let appearance = validate_appearance(&appearance)?;
+    update_workspace(&app, &workspace_id, |ws| ws.appearance = appearance)
+}
+
+/// Archive or trash a workspace. The last active personal workspace cannot
+/// go; if the current workspace goes, another active one takes over.
+fn deactivate(
+    app: &tauri::AppHandle,
+    workspace_id: &str,
+    apply: impl FnOnce(&mut Workspace) -> AppResult<()>,
+) -> AppResult<Workspace> {
+    let workspace_id = require_workspace_id(workspace_id)?;
+
+    let mut updated = None;
+    let mut was_active = false;
+    let mut was_current = false;
+    let state = update_state(app, |state| {
+        was_current = state.current_workspace_id.as_deref() == Some(workspace_id);
+        let (ws, active) = deactivate_in(state, workspace_id, now_unix(), apply)?;
+        updated = Some(ws);
+        was_active = active;
+        Ok(())
+    })?;
+
+    let updated = updated.ok_or_else(|| workspace_not_found(workspace_id))?;
+    if was_active {
+        notify(app, WorkspaceChange::Deactivated(updated.identity()));
+        if was_current {
+            notify_current_replaced(app, &updated.identity(), &state);
+        }
+    }
+    Ok(updated)
+}
+
+/// State half of `deactivate`. Returns the updated workspace and whether it
+/// was active before; the current workspace is handed over by
+/// `ensure_valid_state` when the state is saved.
+fn deactivate_in(
+    state: &mut WorkspaceState,
+    workspace_id: &str,
+    now: i64,
+    apply: impl FnOnce(&mut Workspace) -> AppResult<()>,
+) -> AppResult<(Workspace, bool)> {
+    let personal_left = active_personal_count(state);
+    let ws = state
+        .workspaces
+        .iter_mut()
+        .find(|ws| ws.id == workspace_id)
+        .ok_or_else(|| workspace_not_found(workspace_id))?;
+    let was_active = ws.is_active();
+    if was_active && ws.workspace_type == WorkspaceType::Personal && personal_left <= 1 {
+        return Err(AppError::conflict(
+            "last_personal_workspace",
+            "At least one personal workspace is required",
+        ));
+    }
+    apply(ws)?;
+    ws.updated_at_unix = now;
+    Ok((ws.clone(), was_active))
+}
+
+/// Undo `deactivate`. A workspace that becomes active counts against the plan again.
+fn reactivate(
+    app: &tauri::AppHandle,
+    workspace_id: &str,
+    apply: impl FnOnce(&mut Workspace) -> AppResult<()>,
+) -> AppResult<Workspace> {
+    let workspace_id = require_workspace_id(workspace_id)?;
+    let entitlements = entitlements::current(app);
+
+    let mut updated = None;
+    update_state(app, |state| {
+        updated = Some(reactivate_in(state, workspace_id, &entitlements, now_unix(), apply)?);
+        Ok(())
+    })?;
+
+    let updated = updated.ok_or_else(|| workspace_not_found(workspace_id))?;
+    if updated.is_active() {
+        notify(app, WorkspaceChange::Reactivated(updated.identity()));
+    }
+    Ok(updated)
+}
+
+/// State half of `reactivate`.
+fn reactivate_in(
+    state: &mut WorkspaceState,
+    workspace_id: &str,
+    entitlements: &Entitlements,
+    now: i64,
+    apply: impl FnOnce(&mut Workspace) -> AppResult<()>,
+) -> AppResult<Workspace> {
+    let (was_active, workspace_type) = state
+        .workspaces
+        .iter()
+        .find(|ws| ws.id == workspace_id)
+        .map(|ws| (ws.is_active(), ws.workspace_type.clone()))
+        .ok_or_else(|| workspace_not_found(workspace_id))?;
+    let count = count_of_type(state, &workspace_type);
+    let ws = state
+        .workspaces
+        .iter_mut()
+        .find(|ws| ws.id == workspace_id)
+        .ok_or_else(|| workspace_not_found(workspace_id))?;
+    apply(ws)?;
+    if !was_active && ws.is_active() {
+        entitlements.check_workspace_limit(&workspace_type, count)?;
+    }
+    ws.updated_at_unix = now;
+    Ok(ws.clone())
+}
+
+/// Remove the workspaces `keep` rejects, leaving their server ids in
+/// `removed_server_ids` so sync does not pull them again.
+fn remove_for_good(state: &mut WorkspaceState, keep: impl Fn(&Workspace) -> bool) -> Vec<WorkspaceIdentity> {
+    let mut removed = Vec::new();
+    state.workspaces.retain(|ws| {
+        if keep(ws) {
+            return true;
+        }
+        removed.push(ws.identity());
+        false
+    });
+    for identity in &removed {
+        if let Some(server_id) = identity.server_id {
+            state.removed_server_ids.push(server_id);
+        }
+    }
+    removed
+}
+
+fn is_trash_expired(ws: &Workspace, retention_days: i64, now: i64) -> bool {
+    ws.trash_expires_at_unix(retention_days)
+        .is_some_and(|at| at <= now)
+}
+
+/// Purge workspaces whose trash retention has passed. Runs at startup and
+/// whenever the trash is listed.
+pub fn purge_expired_trash(app: &tauri::AppHandle) -> AppResult<usize> {
+    let now = now_unix();
+    let retention_days = preferences::current_preferences(app).trash_retention_days;
+    if !load_state(app)
+        .workspaces
+        .iter()
+        .any(|ws| is_trash_expired(ws, retention_days, now))
+    {
+        return Ok(0);
+    }
+
+    let mut removed = Vec::new();
+    update_state(app, |state| {
+        removed = remove_for_good(state, |ws| !is_trash_expired(ws, retention_days, now));
+        Ok(())
+    })?;
+    for identity in &removed {
+        notify(app, WorkspaceChange::Deleted(identity.clone()));
+    }
+    Ok(removed.len())
+}
+
+#[tauri::command]
+pub fn archive_workspace(app: tauri::AppHandle, workspace_id: String) -> AppResult<Workspace> {
+    deactivate(&app, &workspace_id, |ws| {
+        if !ws.is_active() {
+            return Err(workspace_inactive(&ws.id));
+        }
+        ws.archived_at_unix = Some(now_unix());
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn unarchive_workspace(app: tauri::AppHandle, workspace_id: String) -> AppResult<Workspace> {
+    reactivate(&app, &workspace_id, |ws| {
+        if ws.archived_at_unix.take().is_none() {
+            return Err(AppError::conflict("workspace_not_archived", "Workspace is not archived")
+                .with_details(serde_json::json!({ "workspace_id": ws.id })));
+        }
+        Ok(())
+    })
+}
+
+/// Move a workspace to the trash. `restore_workspace` undoes it until the
+/// trash retention passes.
+#[tauri::command]
+pub fn delete_workspace(app: tauri::AppHandle, workspace_id: String) -> AppResult<Workspace> {
+    deactivate(&app, &workspace_id, |ws| {
+        if ws.is_trashed() {
+            return Err(AppError::conflict("workspace_in_trash", "Workspace is already in the trash")
+                .with_details(serde_json::json!({ "workspace_id": ws.id })));
+        }
+        ws.trashed_at_unix = Some(now_unix());
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn restore_workspace(app: tauri::AppHandle, workspace_id: String) -> AppResult<Workspace> {
+    reactivate(&app, &workspace_id, |ws| {
+        if ws.trashed_at_unix.take().is_none() {
+            return Err(workspace_not_in_trash(&ws.id));
         }
+        Ok(())
+    })
+}
 
-        state.workspaces.retain(|ws| ws.id != workspace_id);
+fn workspace_not_in_trash(workspace_id: &str) -> AppError {
+    AppError::conflict("workspace_not_in_trash", "Workspace is not in the trash")
+        .with_details(serde_json::json!({ "workspace_id": workspace_id }))
+}
 
-        if state.current_workspace_id.as_deref() == Some(workspace_id) {
-            state.current_workspace_id = state.workspaces.first().map(|ws| ws.id.clone());
+#[tauri::command]
+pub fn list_workspace_trash(app: tauri::AppHandle) -> AppResult<Vec<TrashedWorkspace>> {
+    purge_expired_trash(&app)?;
+    let retention_days = preferences::current_preferences(&app).trash_retention_days;
+    let mut trash: Vec<TrashedWorkspace> = load_state(&app)
+        .workspaces
+        .into_iter()
+        .filter_map(|ws| {
+            let expires_at_unix = ws.trash_expires_at_unix(retention_days)?;
+            Some(TrashedWorkspace {
+                workspace: ws,
+                expires_at_unix,
+            })
+        })
+        .collect();
+    trash.sort_by_key(|item| item.expires_at_unix);
+    Ok(trash)
+}
+
+/// Permanently delete a workspace from the trash.
+#[tauri::command]
+pub fn purge_workspace(app: tauri::AppHandle, workspace_id: String) -> AppResult<()> {
+    let workspace_id = require_workspace_id(&workspace_id)?;
+
+    let mut removed = Vec::new();
+    update_state(&app, |state| {
+        let target = state
+            .workspaces
+            .iter()
+            .find(|ws| ws.id == workspace_id)
+            .ok_or_else(|| workspace_not_found(workspace_id))?;
+        if !target.is_trashed() {
+            return Err(workspace_not_in_trash(workspace_id));
         }
+        removed = remove_for_good(state, |ws| ws.id != workspace_id);
         Ok(())
     })?;
 
+    for identity in removed {
+        notify(&app, WorkspaceChange::Deleted(identity));
+    }
     Ok(())
 }
 
+/// Decided by the cached entitlements; the plan is never taken from the caller.
 #[tauri::command]
-pub fn get_team_workspace_creation_path(plan_tier: String) -> TeamWorkspaceCreationPath {
-    if is_paid_plan(&plan_tier) {
-        TeamWorkspaceCreationPath {
+pub fn get_team_workspace_creation_path(app: tauri::AppHandle) -> TeamWorkspaceCreationPath {
+    let state = load_state(&app);
+    let check = entitlements::current(&app)
+        .check_workspace_limit(&WorkspaceType::Team, count_of_type(&state, &WorkspaceType::Team));
+    match check {
+        Ok(()) => TeamWorkspaceCreationPath {
             status: "allowed".to_string(),
             route: "/workspace/new/team".to_string(),
             message: "현재 플랜에서 팀 워크스페이스를 생성할 수 있습니다.".to_string(),
-        }
-    } else {
-        TeamWorkspaceCreationPath {
-            status: "upgrade_required".to_string(),
-            route: "/billing/upgrade?target=team_workspace".to_string(),
+        },
+        Err(e) => TeamWorkspaceCreationPath {
+            status: e.code.clone(),
+            route: e.details["route"]
+                .as_str()
+                .unwrap_or("/billing/upgrade?target=team_workspace")
+                .to_string(),
             message: "팀 워크스페이스 생성을 위해 유료 플랜 업그레이드가 필요합니다.".to_string(),
-        }
+        },
     }
 }
 
@@ -350,30 +1103,54 @@ pub fn get_team_workspace_creation_path(plan_tier: String) -> TeamWorkspaceCreat
 pub fn create_team_workspace(
     app: tauri::AppHandle,
     name: String,
-    plan_tier: String,
     team_id: Option<i64>,
-) -> Result<TeamWorkspaceCreationResult, String> {
+    template_id: Option<String>,
+) -> AppResult<TeamWorkspaceCreationResult> {
     let name = validate_workspace_name(&name)?;
-    let normalized_plan = normalize_plan(&plan_tier);
+    let template = template_id
+        .as_deref()
+        .map(|id| workspace_template::find_template(&app, id, &WorkspaceType::Team))
+        .transpose()?;
+    let entitlements = entitlements::current(&app);
+    let plan_tier = entitlements.plan_name.trim().to_lowercase();
 
     let mut created: Option<Workspace> = None;
-    update_state(&app, |state| {
+    let mut previous = None;
+    let state = update_state(&app, |state| {
+        entitlements.check_workspace_limit(
+            &WorkspaceType::Team,
+            count_of_type(state, &WorkspaceType::Team),
+        )?;
         let now = now_unix();
-        let resolved_team_id = team_id.unwrap_or_else(new_team_id);
         let workspace = Workspace {
             id: new_workspace_id(),
             name: name.clone(),
             workspace_type: WorkspaceType::Team,
-            plan_tier: Some(normalized_plan.clone()),
-            team_id: Some(resolved_team_id),
+            plan_tier: Some(plan_tier.clone()),
+            // Only a real server team id; never made up locally.
+            team_id,
             created_at_unix: now,
             updated_at_unix: now,
+            server_id: None,
+            synced_name: None,
+            archived_at_unix: None,
+            trashed_at_unix: None,
+            sort_order: next_sort_order(state),
+            pinned: false,
+            appearance: template
+                .as_ref()
+                .map(|t| t.appearance.clone())
+                .unwrap_or_default(),
+            pending_template_id: template.as_ref().map(|t| t.id.clone()),
         };
-        state.current_workspace_id = Some(workspace.id.clone());
+        previous = state.current_workspace_id.replace(workspace.id.clone());
         state.workspaces.push(workspace.clone());
         created = Some(workspace);
         Ok(())
     })?;
+    if let Some(workspace) = &created {
+        notify_created(&app, &state, workspace, previous);
+    }
 
     Ok(TeamWorkspaceCreationResult {
         status: "created".to_string(),
@@ -382,3 +1159,276 @@ pub fn create_team_workspace(
         workspace: created,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_750_000_000;
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn workspace(id: &str, workspace_type: WorkspaceType, server_id: Option<i64>) -> Workspace {
+        Workspace {
+            id: id.to_string(),
+            name: id.to_string(),
+            workspace_type,
+            server_id,
+            ..default_personal_workspace()
+        }
+    }
+
+    fn state_of(workspaces: Vec<Workspace>, current: &str) -> WorkspaceState {
+        WorkspaceState {
+            current_workspace_id: Some(current.to_string()),
+            workspaces,
+            ..WorkspaceState::default()
+        }
+    }
+
+    fn trash(ws: &mut Workspace) -> AppResult<()> {
+        ws.trashed_at_unix = Some(NOW);
+        Ok(())
+    }
+
+    fn archive(ws: &mut Workspace) -> AppResult<()> {
+        ws.archived_at_unix = Some(NOW);
+        Ok(())
+    }
+
+    fn restore(ws: &mut Workspace) -> AppResult<()> {
+        ws.trashed_at_unix = None;
+        ws.archived_at_unix = None;
+        Ok(())
+    }
+
+    #[test]
+    fn last_active_personal_workspace_cannot_go() {
+        let mut state = state_of(
+            vec![
+                workspace("a", WorkspaceType::Personal, None),
+                workspace("b", WorkspaceType::Personal, None),
+                workspace("team", WorkspaceType::Team, Some(9)),
+            ],
+            "a",
+        );
+        deactivate_in(&mut state, "b", NOW, archive).unwrap();
+
+        let err = deactivate_in(&mut state, "a", NOW, trash).unwrap_err();
+        assert_eq!(err.code, "last_personal_workspace");
+        assert!(state.workspaces[0].is_active());
+
+        // Team workspaces never count as the last one.
+        let (team, was_active) = deactivate_in(&mut state, "team", NOW, trash).unwrap();
+        assert!(was_active);
+        assert_eq!(team.trashed_at_unix, Some(NOW));
+        assert_eq!(team.updated_at_unix, NOW);
+    }
+
+    #[test]
+    fn current_workspace_hands_over_when_deactivated() {
+        let mut state = state_of(
+            vec![
+                workspace("a", WorkspaceType::Personal, None),
+                workspace("b", WorkspaceType::Personal, None),
+            ],
+            "a",
+        );
+        deactivate_in(&mut state, "a", NOW, trash).unwrap();
+        let state = ensure_valid_state(state);
+        assert_eq!(state.current_workspace_id.as_deref(), Some("b"));
+
+        let from = state.workspaces[0].identity();
+        let handed_over = changes_between(&state_of(state.workspaces.clone(), "a"), &state);
+        assert!(matches!(
+            handed_over.as_slice(),
+            [WorkspaceChange::Switched { from: Some(f), to }] if *f == from && to.local_id == "b"
+        ));
+    }
+
+    #[test]
+    fn deactivating_an_inactive_workspace_reports_it() {
+        let mut state = state_of(
+            vec![
+                workspace("a", WorkspaceType::Personal, None),
+                workspace("b", WorkspaceType::Personal, None),
+            ],
+            "a",
+        );
+        deactivate_in(&mut state, "b", NOW, archive).unwrap();
+        let (_, was_active) = deactivate_in(&mut state, "b", NOW, trash).unwrap();
+        assert!(!was_active);
+        assert_eq!(
+            deactivate_in(&mut state, "missing", NOW, trash)
+                .unwrap_err()
+                .code,
+            "workspace_not_found"
+        );
+    }
+
+    #[test]
+    fn restore_rechecks_the_plan_limit() {
+        let mut state = state_of(
+            vec![
+                workspace("a", WorkspaceType::Personal, None),
+                workspace("t1", WorkspaceType::Team, Some(1)),
+                workspace("t2", WorkspaceType::Team, Some(2)),
+            ],
+            "a",
+        );
+        deactivate_in(&mut state, "t2", NOW, trash).unwrap();
+        let one_team = Entitlements {
+            max_team_workspaces: Some(1),
+            ..Entitlements::free()
+        };
+
+        let err = reactivate_in(&mut state, "t2", &one_team, NOW, restore).unwrap_err();
+        assert_eq!(err.code, "upgrade_required");
+        assert_eq!(err.details["current"], 1);
+
+        // Archived workspaces do not count, so one can make room.
+        deactivate_in(&mut state, "t1", NOW, archive).unwrap();
+        let restored = reactivate_in(&mut state, "t2", &one_team, NOW, restore).unwrap();
+        assert!(restored.is_active());
+        assert!(reactivate_in(&mut state, "t1", &one_team, NOW, restore).is_err());
+    }
+
+    #[test]
+    fn trash_expires_after_the_retention() {
+        let mut trashed = workspace("a", WorkspaceType::Personal, None);
+        trashed.trashed_at_unix = Some(NOW);
+        assert_eq!(trashed.trash_expires_at_unix(30), Some(NOW + 30 * DAY));
+        assert!(!is_trash_expired(&trashed, 30, NOW + 30 * DAY - 1));
+        assert!(is_trash_expired(&trashed, 30, NOW + 30 * DAY));
+        assert!(is_trash_expired(&trashed, 7, NOW + 7 * DAY));
+
+        let mut archived = workspace("b", WorkspaceType::Personal, None);
+        archived.archived_at_unix = Some(NOW);
+        assert_eq!(archived.trash_expires_at_unix(30), None);
+        assert!(!is_trash_expired(&archived, 30, NOW + 365 * DAY));
+    }
+
+    #[test]
+    fn purged_workspaces_leave_server_tombstones() {
+        let mut local = workspace("local", WorkspaceType::Personal, None);
+        local.trashed_at_unix = Some(NOW - 40 * DAY);
+        let mut synced = workspace("synced", WorkspaceType::Team, Some(42));
+        synced.trashed_at_unix = Some(NOW - 40 * DAY);
+        let mut recent = workspace("recent", WorkspaceType::Team, Some(43));
+        recent.trashed_at_unix = Some(NOW - DAY);
+        let mut state = state_of(
+            vec![
+                workspace("a", WorkspaceType::Personal, None),
+                local,
+                synced,
+                recent,
+            ],
+            "a",
+        );
+
+        let removed = remove_for_good(&mut state, |ws| !is_trash_expired(ws, 30, NOW));
+        let removed: Vec<_> = removed.iter().map(|w| w.local_id.as_str()).collect();
+        assert_eq!(removed, ["local", "synced"]);
+        assert_eq!(state.removed_server_ids, [42]);
+        let left: Vec<_> = state.workspaces.iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(left, ["a", "recent"]);
+    }
+
+    fn ids(workspaces: &[Workspace]) -> Vec<&str> {
+        workspaces.iter().map(|ws| ws.id.as_str()).collect()
+    }
+
+    fn ordered(ids: &[&str]) -> WorkspaceState {
+        let workspaces = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| Workspace {
+                sort_order: index as i64,
+                ..workspace(id, WorkspaceType::Personal, None)
+            })
+            .collect();
+        state_of(workspaces, ids[0])
+    }
+
+    #[test]
+    fn listed_workspaces_move_first() {
+        let mut state = ordered(&["a", "b", "c", "d", "e"]);
+        reorder_in(&mut state, &["d".to_string(), "b".to_string()]).unwrap();
+        assert_eq!(ids(&state.workspaces), ["d", "b", "a", "c", "e"]);
+        let orders: Vec<_> = state.workspaces.iter().map(|ws| ws.sort_order).collect();
+        assert_eq!(orders, [0, 1, 2, 3, 4]);
+
+        // Unlisted workspaces keep their relative order, not their position.
+        reorder_in(&mut state, &["e".to_string()]).unwrap();
+        assert_eq!(ids(&state.workspaces), ["e", "d", "b", "a", "c"]);
+    }
+
+    #[test]
+    fn reorder_rejects_unknown_ids() {
+        let mut state = ordered(&["a", "b"]);
+        let err = reorder_in(&mut state, &["b".to_string(), "x".to_string()]).unwrap_err();
+        assert_eq!(err.code, "workspace_not_found");
+        assert_eq!(ids(&state.workspaces), ["a", "b"]);
+    }
+
+    #[test]
+    fn pinned_workspaces_are_displayed_first() {
+        let mut state = ordered(&["a", "b", "c", "d"]);
+        state.workspaces[1].pinned = true;
+        state.workspaces[3].pinned = true;
+        sort_for_display(&mut state.workspaces);
+        assert_eq!(ids(&state.workspaces), ["b", "d", "a", "c"]);
+    }
+
+    fn appearance(
+        color: Option<&str>,
+        icon: Option<&str>,
+        view: Option<&str>,
+    ) -> WorkspaceAppearance {
+        WorkspaceAppearance {
+            color: color.map(str::to_string),
+            icon: icon.map(str::to_string),
+            default_calendar_view: view.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn appearance_is_normalized() {
+        let valid = validate_appearance(&appearance(Some(" #3b82f6 "), Some(" 📅 "), Some("week")))
+            .unwrap();
+        assert_eq!(valid, appearance(Some("#3B82F6"), Some("📅"), Some("week")));
+        let short = validate_appearance(&appearance(Some("#abc"), Some("calendar"), None)).unwrap();
+        assert_eq!(short.color.as_deref(), Some("#AABBCC"));
+        // Blank values fall back to the preferences.
+        let blank = validate_appearance(&appearance(Some(" "), Some(""), Some(" "))).unwrap();
+        assert_eq!(blank, WorkspaceAppearance::default());
+    }
+
+    #[test]
+    fn invalid_appearance_is_rejected() {
+        let code = |a: WorkspaceAppearance| validate_appearance(&a).unwrap_err().code;
+        assert_eq!(
+            code(appearance(Some("blue"), None, None)),
+            "workspace_color_invalid"
+        );
+        assert_eq!(
+            code(appearance(Some("#12345"), None, None)),
+            "workspace_color_invalid"
+        );
+        assert_eq!(
+            code(appearance(
+                None,
+                Some(&"x".repeat(MAX_WORKSPACE_ICON_LEN + 1)),
+                None
+            )),
+            "workspace_icon_too_long"
+        );
+        assert_eq!(
+            code(appearance(None, Some("two words"), None)),
+            "workspace_icon_invalid"
+        );
+        assert_eq!(
+            code(appearance(None, None, Some("agenda"))),
+            "workspace_view_unsupported"
+        );
+    }
+}
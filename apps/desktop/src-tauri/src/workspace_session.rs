@@ -0,0 +1,216 @@
+/// Per-workspace UI memory: the last calendar view, selected date and main
+/// window layout, so switching back restores a workspace where it was left.
+///
+/// The frontend saves view and date; the window layout is captured here when
+/// a workspace is switched away from, and applied when it is switched back to.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::Manager;
+
+use crate::config_store;
+use crate::error::{AppError, AppResult};
+use crate::migrations;
+use crate::preferences;
+use crate::time::now_unix;
+use crate::workspace::{self, WorkspaceChange};
+
+const SESSIONS_FILE: &str = migrations::WORKSPACE_SESSIONS.file;
+const MAIN_WINDOW: &str = "main";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WindowLayout {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceSession {
+    #[serde(default)]
+    pub calendar_view: Option<String>,
+    /// `YYYY-MM-DD`.
+    #[serde(default)]
+    pub selected_date: Option<String>,
+    #[serde(default)]
+    pub window: Option<WindowLayout>,
+    #[serde(default)]
+    pub updated_at_unix: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceSessions {
+    /// Keyed by local workspace id.
+    #[serde(default)]
+    pub sessions: HashMap<String, WorkspaceSession>,
+}
+
+fn sessions_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    config_store::config_path(app, SESSIONS_FILE)
+}
+
+fn load_sessions(app: &tauri::AppHandle) -> WorkspaceSessions {
+    sessions_path(app)
+        .ok()
+        .and_then(|path| config_store::read(&path, &migrations::WORKSPACE_SESSIONS))
+        .unwrap_or_default()
+}
+
+fn update_sessions(app: &tauri::AppHandle, f: impl FnOnce(&mut WorkspaceSessions)) -> AppResult<()> {
+    let path = sessions_path(app).map_err(AppError::storage)?;
+    config_store::with_lock(&path, || {
+        let mut sessions = load_sessions(app);
+        f(&mut sessions);
+        // Resolved again in case the data directory moved while waiting.
+        let path = sessions_path(app).map_err(AppError::storage)?;
+        config_store::write(&path, &migrations::WORKSPACE_SESSIONS, &sessions).map_err(AppError::storage)
+    })?
+}
+
+/// Replace every session. Used by backup restore.
+pub fn replace_sessions(app: &tauri::AppHandle, incoming: WorkspaceSessions) -> AppResult<()> {
+    update_sessions(app, |sessions| *sessions = incoming)
+}
+
+/// Remove `workspace_sessions.json`. Used on logout.
+pub fn clear_sessions(app: &tauri::AppHandle) -> Result<(), String> {
+    config_store::remove(&sessions_path(app)?)
+}
+
+/// The saved session, or one seeded from the workspace's appearance the
+/// first time it is opened.
+pub fn session_for(app: &tauri::AppHandle, workspace_id: &str) -> Option<WorkspaceSession> {
+    if let Some(session) = load_sessions(app).sessions.remove(workspace_id) {
+        return Some(session);
+    }
+    let view = workspace::load_state(app)
+        .workspaces
+        .into_iter()
+        .find(|ws| ws.id == workspace_id)?
+        .appearance
+        .default_calendar_view?;
+    Some(WorkspaceSession {
+        calendar_view: Some(view),
+        ..WorkspaceSession::default()
+    })
+}
+
+fn capture_main_window(app: &tauri::AppHandle) -> Option<WindowLayout> {
+    let window = app.get_webview_window(MAIN_WINDOW)?;
+    let position = window.outer_position().ok()?;
+    let size = window.outer_size().ok()?;
+    if size.width == 0 || size.height == 0 {
+        return None;
+    }
+    Some(WindowLayout {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+fn apply_main_window(app: &tauri::AppHandle, layout: &WindowLayout) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    let _ = window.set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+        x: layout.x,
+        y: layout.y,
+    }));
+    let _ = window.set_size(tauri::Size::Physical(tauri::PhysicalSize {
+        width: layout.width,
+        height: layout.height,
+    }));
+}
+
+fn on_workspace_changed(app: &tauri::AppHandle, change: &WorkspaceChange) {
+    let result = match change {
+        WorkspaceChange::Switched { from, to } => {
+            let layout = capture_main_window(app);
+            let restored = load_sessions(app)
+                .sessions
+                .get(&to.local_id)
+                .and_then(|session| session.window);
+            if let Some(restored) = restored {
+                apply_main_window(app, &restored);
+            }
+            match (from, layout) {
+                (Some(from), Some(layout)) => update_sessions(app, |sessions| {
+                    let session = sessions.sessions.entry(from.local_id.clone()).or_default();
+                    session.window = Some(layout);
+                    session.updated_at_unix = now_unix();
+                }),
+                _ => Ok(()),
+            }
+        }
+        WorkspaceChange::Deleted(identity) => update_sessions(app, |sessions| {
+            sessions.sessions.remove(&identity.local_id);
+        }),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        log::warn!("workspace session: failed to persist: {}", e);
+    }
+}
+
+pub fn subscribe_to_workspaces(app: &tauri::AppHandle) {
+    workspace::subscribe(app, Box::new(on_workspace_changed));
+}
+
+#[tauri::command]
+pub fn get_workspace_session(app: tauri::AppHandle, workspace_id: String) -> Option<WorkspaceSession> {
+    session_for(&app, workspace_id.trim())
+}
+
+/// Remember the calendar view and date shown for a workspace. Omitted
+/// fields keep their saved value.
+#[tauri::command]
+pub fn save_workspace_session(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    calendar_view: Option<String>,
+    selected_date: Option<String>,
+) -> AppResult<WorkspaceSession> {
+    let workspace_id = workspace_id.trim().to_string();
+    if !workspace::load_state(&app)
+        .workspaces
+        .iter()
+        .any(|ws| ws.id == workspace_id)
+    {
+        return Err(workspace::workspace_not_found(&workspace_id));
+    }
+
+    let calendar_view = calendar_view.map(|view| view.trim().to_string());
+    if let Some(view) = &calendar_view {
+        if !preferences::CALENDAR_VIEWS.contains(&view.as_str()) {
+            return Err(AppError::validation(
+                "workspace_view_unsupported",
+                format!("Calendar view must be one of: {}", preferences::CALENDAR_VIEWS.join(", ")),
+            )
+            .with_details(serde_json::json!({ "allowed": preferences::CALENDAR_VIEWS })));
+        }
+    }
+    let selected_date = selected_date.map(|date| date.trim().to_string());
+    if let Some(date) = &selected_date {
+        if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return Err(AppError::validation("invalid_date", "selected_date must be YYYY-MM-DD")
+                .with_details(serde_json::json!({ "selected_date": date })));
+        }
+    }
+
+    let mut saved = WorkspaceSession::default();
+    update_sessions(&app, |sessions| {
+        let session = sessions.sessions.entry(workspace_id.clone()).or_default();
+        if calendar_view.is_some() {
+            session.calendar_view = calendar_view;
+        }
+        if selected_date.is_some() {
+            session.selected_date = selected_date;
+        }
+        session.updated_at_unix = now_unix();
+        saved = session.clone();
+    })?;
+    Ok(saved)
+}
@@ -0,0 +1,171 @@
+/// Local data directory of each workspace, `workspaces/<local id>/` under the
+/// data directory, with `cache/` and `attachments/` inside.
+///
+/// Directories follow the workspace lifecycle: created with the workspace,
+/// kept while it is archived or in the trash, and removed when it is deleted
+/// for good. Directories of workspaces that no longer exist are moved to
+/// `.orphaned/` on startup rather than deleted, since the state they are
+/// checked against may have been restored or imported.
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config_store;
+use crate::error::{AppError, AppResult};
+use crate::time::now_unix;
+use crate::workspace::{self, WorkspaceChange};
+
+pub const WORKSPACE_DATA_DIR: &str = "workspaces";
+const CACHE_DIR: &str = "cache";
+const ATTACHMENTS_DIR: &str = "attachments";
+/// Never a workspace id, see `is_safe_id`.
+const ORPHANED_DIR: &str = ".orphaned";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceDataDirs {
+    pub workspace_id: String,
+    pub root: String,
+    pub cache: String,
+    pub attachments: String,
+}
+
+fn data_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    config_store::config_path(app, WORKSPACE_DATA_DIR)
+}
+
+/// Local ids are generated (`ws_<uuid>`), but the state file can be edited or
+/// imported; never let one escape the data directory.
+fn is_safe_id(local_id: &str) -> bool {
+    !local_id.is_empty()
+        && local_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Root of a workspace's data, not created.
+pub fn workspace_dir(app: &tauri::AppHandle, local_id: &str) -> Result<PathBuf, String> {
+    if !is_safe_id(local_id) {
+        return Err(format!("Invalid workspace id for a data directory: {}", local_id));
+    }
+    Ok(data_root(app)?.join(local_id))
+}
+
+pub fn cache_dir(app: &tauri::AppHandle, local_id: &str) -> Result<PathBuf, String> {
+    Ok(workspace_dir(app, local_id)?.join(CACHE_DIR))
+}
+
+pub fn attachments_dir(app: &tauri::AppHandle, local_id: &str) -> Result<PathBuf, String> {
+    Ok(workspace_dir(app, local_id)?.join(ATTACHMENTS_DIR))
+}
+
+/// Create the directories of a workspace if missing.
+pub fn ensure_dirs(app: &tauri::AppHandle, local_id: &str) -> Result<WorkspaceDataDirs, String> {
+    let root = workspace_dir(app, local_id)?;
+    let cache = cache_dir(app, local_id)?;
+    let attachments = attachments_dir(app, local_id)?;
+    for dir in [&cache, &attachments] {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    Ok(WorkspaceDataDirs {
+        workspace_id: local_id.to_string(),
+        root: root.to_string_lossy().to_string(),
+        cache: cache.to_string_lossy().to_string(),
+        attachments: attachments.to_string_lossy().to_string(),
+    })
+}
+
+/// Remove everything stored for a workspace.
+pub fn remove_dirs(app: &tauri::AppHandle, local_id: &str) -> Result<(), String> {
+    let root = workspace_dir(app, local_id)?;
+    if root.exists() {
+        fs::remove_dir_all(&root).map_err(|e| format!("Failed to remove {}: {}", root.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Move directories of workspaces that are gone, e.g. deleted while the app
+/// was not running, to `.orphaned/<id>-<unix>`. Skipped unless the workspace
+/// state was actually read: a missing or corrupt state file would make every
+/// directory look orphaned.
+pub fn prune_orphans(app: &tauri::AppHandle) -> Result<Vec<String>, String> {
+    let root = data_root(app)?;
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+    let Some(state) = workspace::read_stored_state(app) else {
+        log::warn!("workspace storage: workspace state unreadable, orphan check skipped");
+        return Ok(Vec::new());
+    };
+    let entries = fs::read_dir(&root).map_err(|e| format!("Failed to read {}: {}", root.display(), e))?;
+    let orphaned = root.join(ORPHANED_DIR);
+    let mut moved = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().is_dir() || !is_safe_id(&name) || state.workspaces.iter().any(|ws| ws.id == name) {
+            continue;
+        }
+        fs::create_dir_all(&orphaned).map_err(|e| format!("Failed to create {}: {}", orphaned.display(), e))?;
+        let target = orphaned.join(format!("{}-{}", name, now_unix()));
+        fs::rename(entry.path(), &target).map_err(|e| format!("Failed to move {}: {}", name, e))?;
+        moved.push(name);
+    }
+    Ok(moved)
+}
+
+/// Remove every workspace directory. Used on logout.
+pub fn clear_all(app: &tauri::AppHandle) -> Result<(), String> {
+    let root = data_root(app)?;
+    if root.exists() {
+        fs::remove_dir_all(&root).map_err(|e| format!("Failed to remove workspace data: {}", e))?;
+    }
+    Ok(())
+}
+
+fn on_workspace_changed(app: &tauri::AppHandle, change: &WorkspaceChange) {
+    let result = match change {
+        WorkspaceChange::Created(identity) => ensure_dirs(app, &identity.local_id).map(|_| ()),
+        WorkspaceChange::Deleted(identity) => remove_dirs(app, &identity.local_id),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        log::warn!("workspace storage: {}", e);
+    }
+}
+
+/// Register last so the directory outlives the other modules' cleanup.
+pub fn subscribe_to_workspaces(app: &tauri::AppHandle) {
+    workspace::subscribe(app, Box::new(on_workspace_changed));
+}
+
+fn existing_workspace(app: &tauri::AppHandle, workspace_id: &str) -> AppResult<()> {
+    if workspace::load_state(app)
+        .workspaces
+        .iter()
+        .any(|ws| ws.id == workspace_id)
+    {
+        Ok(())
+    } else {
+        Err(workspace::workspace_not_found(workspace_id))
+    }
+}
+
+/// Paths of a workspace's data directories, created on first use.
+#[tauri::command]
+pub fn get_workspace_data_dirs(app: tauri::AppHandle, workspace_id: String) -> AppResult<WorkspaceDataDirs> {
+    let workspace_id = workspace_id.trim();
+    existing_workspace(&app, workspace_id)?;
+    ensure_dirs(&app, workspace_id).map_err(AppError::storage)
+}
+
+/// Empty a workspace's cache directory; attachments are kept.
+#[tauri::command]
+pub fn clear_workspace_cache(app: tauri::AppHandle, workspace_id: String) -> AppResult<()> {
+    let workspace_id = workspace_id.trim();
+    existing_workspace(&app, workspace_id)?;
+    let dir = cache_dir(&app, workspace_id).map_err(AppError::storage)?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir)
+            .map_err(|e| AppError::storage(format!("Failed to clear cache: {}", e)))?;
+    }
+    fs::create_dir_all(&dir).map_err(|e| AppError::storage(format!("Failed to create cache: {}", e)))
+}
@@ -0,0 +1,772 @@
+/// Two-way reconciliation of the local `WorkspaceState` with the server.
+///
+/// Each local workspace links to a server workspace through `server_id`, and
+/// `synced_name` records the name both sides last agreed on, so renames are
+/// detected as a three-way merge:
+/// - unlinked local entries are matched to unlinked server entries by type
+///   and name (the first-launch default adopts the server's personal
+///   workspace); the rest are created on the server
+/// - a local rename is pushed unless the server renamed it too (conflict)
+/// - server renames, new server workspaces and server deletions are pulled,
+///   except deleting a workspace with an unpushed rename (conflict)
+///
+/// Network calls run against a snapshot; the result is merged into the
+/// freshly loaded state under the workspace lock.
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::account;
+use crate::api::{ApiClient, ApiError};
+use crate::error::AppResult;
+use crate::preferences;
+use crate::time::{self, now_unix};
+use crate::workspace_template;
+use crate::workspace::{
+    self, Workspace, WorkspaceAppearance, WorkspaceChange, WorkspaceIdMap, WorkspaceState, WorkspaceType,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerWorkspace {
+    pub workspace_id: i64,
+    #[serde(rename = "type")]
+    pub workspace_type: WorkspaceType,
+    /// member_id for personal workspaces, team_id for team workspaces.
+    pub owner_id: i64,
+    pub name: String,
+    /// Server wall-clock time, see `time::parse_server_datetime`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspacesResponse {
+    #[serde(default)]
+    workspaces: Vec<ServerWorkspace>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateWorkspaceResponse {
+    workspace: ServerWorkspace,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateWorkspaceRequest<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    workspace_type: &'a WorkspaceType,
+    owner_id: i64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncConflictKind {
+    /// Renamed locally and on the server to different names.
+    RenamedOnBoth,
+    /// Deleted on the server while a local rename was pending.
+    DeletedOnServer,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflict {
+    pub workspace_id: String,
+    pub kind: SyncConflictKind,
+    pub local_name: String,
+    pub server_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncFailure {
+    pub workspace_id: String,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorkspaceSyncReport {
+    pub created_on_server: Vec<String>,
+    pub renamed_on_server: Vec<String>,
+    pub added_locally: Vec<String>,
+    pub renamed_locally: Vec<String>,
+    pub removed_locally: Vec<String>,
+    /// Workspaces whose pending template was applied on the server.
+    pub templates_applied: Vec<String>,
+    pub conflicts: Vec<SyncConflict>,
+    /// Pushes that failed; they are retried on the next sync.
+    pub failures: Vec<SyncFailure>,
+    pub synced_at_unix: i64,
+}
+
+/// What the network phase did, applied later by `merge`.
+#[derive(Debug, Default)]
+pub struct SyncExchange {
+    server: Vec<ServerWorkspace>,
+    /// Local id → server id for entries matched without a create.
+    links: HashMap<String, i64>,
+    created: HashMap<String, ServerWorkspace>,
+    /// Local id → name now stored on the server.
+    renamed: HashMap<String, String>,
+    failures: Vec<SyncFailure>,
+}
+
+fn has_pending_rename(ws: &Workspace) -> bool {
+    ws.server_id.is_some() && ws.synced_name.as_deref() != Some(ws.name.as_str())
+}
+
+/// Link unlinked local workspaces to unlinked server workspaces.
+fn match_unlinked(local: &WorkspaceState, server: &[ServerWorkspace]) -> HashMap<String, i64> {
+    let linked: HashSet<i64> = local.workspaces.iter().filter_map(|ws| ws.server_id).collect();
+    let mut free: Vec<&ServerWorkspace> = server
+        .iter()
+        .filter(|sw| !linked.contains(&sw.workspace_id))
+        .filter(|sw| !local.removed_server_ids.contains(&sw.workspace_id))
+        .collect();
+    let unlinked: Vec<&Workspace> = local
+        .workspaces
+        .iter()
+        .filter(|ws| ws.server_id.is_none() && !ws.is_trashed())
+        .collect();
+
+    let mut links = HashMap::new();
+    let mut take = |ws: &Workspace, pick: &dyn Fn(&ServerWorkspace) -> bool| {
+        if links.contains_key(&ws.id) {
+            return;
+        }
+        if let Some(pos) = free
+            .iter()
+            .position(|sw| sw.workspace_type == ws.workspace_type && pick(sw))
+        {
+            links.insert(ws.id.clone(), free.remove(pos).workspace_id);
+        }
+    };
+    for ws in &unlinked {
+        take(ws, &|sw| sw.name.trim() == ws.name.trim());
+    }
+    for ws in unlinked
+        .iter()
+        .filter(|ws| ws.workspace_type == WorkspaceType::Personal && ws.name == workspace::DEFAULT_WORKSPACE_NAME)
+    {
+        take(ws, &|_| true);
+    }
+    links
+}
+
+fn failure(workspace_id: &str, e: &ApiError) -> SyncFailure {
+    let code = match e {
+        ApiError::Offline(_) => "server_unreachable",
+        ApiError::Unauthorized => "unauthorized",
+        ApiError::Status(404) => "not_found",
+        ApiError::Status(403) => "forbidden",
+        ApiError::Status(_) => "server_error",
+        ApiError::Decode(_) => "invalid_server_response",
+    };
+    SyncFailure {
+        workspace_id: workspace_id.to_string(),
+        code: code.to_string(),
+        message: e.to_string(),
+    }
+}
+
+/// Fetch server workspaces and push local creates and renames. Only the
+/// initial fetch is fatal; individual push failures are reported.
+pub async fn exchange(
+    client: &ApiClient,
+    local: &WorkspaceState,
+    member_id: Option<i64>,
+) -> Result<SyncExchange, ApiError> {
+    let response: WorkspacesResponse = client.get("/api/me/workspaces").await?;
+    let mut exchange = SyncExchange {
+        links: match_unlinked(local, &response.workspaces),
+        server: response.workspaces,
+        ..SyncExchange::default()
+    };
+    let server_names: HashMap<i64, &str> = exchange
+        .server
+        .iter()
+        .map(|sw| (sw.workspace_id, sw.name.as_str()))
+        .collect();
+
+    for ws in &local.workspaces {
+        match ws.server_id {
+            // Trashed before it ever reached the server: nothing to push.
+            None if ws.is_trashed() => {}
+            None if !exchange.links.contains_key(&ws.id) => {
+                let owner_id = match ws.workspace_type {
+                    WorkspaceType::Personal => member_id,
+                    WorkspaceType::Team => ws.team_id,
+                };
+                let Some(owner_id) = owner_id else {
+                    exchange.failures.push(SyncFailure {
+                        workspace_id: ws.id.clone(),
+                        code: "owner_unknown".to_string(),
+                        message: "Workspace owner is unknown; sign in again".to_string(),
+                    });
+                    continue;
+                };
+                let request = CreateWorkspaceRequest {
+                    name: &ws.name,
+                    workspace_type: &ws.workspace_type,
+                    owner_id,
+                };
+                match client
+                    .post::<_, CreateWorkspaceResponse>("/api/workspaces", &request)
+                    .await
+                {
+                    Ok(created) => {
+                        exchange.created.insert(ws.id.clone(), created.workspace);
+                    }
+                    Err(e) => exchange.failures.push(failure(&ws.id, &e)),
+                }
+            }
+            Some(server_id) if has_pending_rename(ws) => {
+                // Renamed on the server as well: leave it to `merge` to report.
+                let Some(server_name) = server_names.get(&server_id) else {
+                    continue;
+                };
+                if ws.synced_name.as_deref() != Some(*server_name) {
+                    continue;
+                }
+                let path = format!("/api/workspaces/{}", server_id);
+                match client
+                    .patch::<_, serde_json::Value>(&path, &serde_json::json!({ "name": ws.name }))
+                    .await
+                {
+                    Ok(_) => {
+                        exchange.renamed.insert(ws.id.clone(), ws.name.clone());
+                    }
+                    Err(e) => exchange.failures.push(failure(&ws.id, &e)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(exchange)
+}
+
+fn server_workspace_to_local(sw: &ServerWorkspace, now: i64, tz: Tz) -> Workspace {
+    let created_at_unix = sw
+        .created_at
+        .as_deref()
+        .and_then(|value| time::parse_server_datetime(value, tz))
+        .unwrap_or(now);
+    Workspace {
+        id: workspace::new_workspace_id(),
+        name: sw.name.clone(),
+        workspace_type: sw.workspace_type.clone(),
+        plan_tier: None,
+        team_id: (sw.workspace_type == WorkspaceType::Team).then_some(sw.owner_id),
+        created_at_unix,
+        updated_at_unix: now,
+        server_id: Some(sw.workspace_id),
+        synced_name: Some(sw.name.clone()),
+        archived_at_unix: None,
+        trashed_at_unix: None,
+        sort_order: 0,
+        pinned: false,
+        appearance: WorkspaceAppearance::default(),
+        pending_template_id: None,
+    }
+}
+
+/// Apply an exchange to the current local state. `tz` reads the server's
+/// naive timestamps.
+pub fn merge(state: &mut WorkspaceState, exchange: SyncExchange, now: i64, tz: Tz) -> WorkspaceSyncReport {
+    let mut report = WorkspaceSyncReport {
+        failures: exchange.failures,
+        synced_at_unix: now,
+        ..WorkspaceSyncReport::default()
+    };
+
+    let mut server: HashMap<i64, ServerWorkspace> = exchange
+        .server
+        .into_iter()
+        .map(|sw| (sw.workspace_id, sw))
+        .collect();
+    for ws in state.workspaces.iter_mut() {
+        if ws.server_id.is_some() {
+            continue;
+        }
+        if let Some(created) = exchange.created.get(&ws.id) {
+            ws.server_id = Some(created.workspace_id);
+            ws.synced_name = Some(created.name.clone());
+            server.insert(created.workspace_id, created.clone());
+            report.created_on_server.push(ws.id.clone());
+        } else if let Some(server_id) = exchange.links.get(&ws.id) {
+            ws.server_id = Some(*server_id);
+        }
+    }
+    for (id, name) in &exchange.renamed {
+        if let Some(ws) = state.workspaces.iter_mut().find(|ws| &ws.id == id) {
+            ws.synced_name = Some(name.clone());
+            if let Some(sw) = ws.server_id.and_then(|sid| server.get_mut(&sid)) {
+                sw.name = name.clone();
+            }
+            report.renamed_on_server.push(id.clone());
+        }
+    }
+
+    let mut removed = Vec::new();
+    for ws in state.workspaces.iter_mut() {
+        let Some(server_id) = ws.server_id else {
+            continue;
+        };
+        let Some(sw) = server.get(&server_id) else {
+            if has_pending_rename(ws) {
+                report.conflicts.push(SyncConflict {
+                    workspace_id: ws.id.clone(),
+                    kind: SyncConflictKind::DeletedOnServer,
+                    local_name: ws.name.clone(),
+                    server_name: None,
+                });
+            } else {
+                removed.push(ws.id.clone());
+            }
+            continue;
+        };
+
+        // Newly matched entries have no base yet; the server name wins.
+        let base = ws.synced_name.clone().unwrap_or_else(|| sw.name.clone());
+        let local_changed = ws.name != base;
+        let server_changed = sw.name != base;
+        if server_changed && local_changed && ws.name != sw.name {
+            report.conflicts.push(SyncConflict {
+                workspace_id: ws.id.clone(),
+                kind: SyncConflictKind::RenamedOnBoth,
+                local_name: ws.name.clone(),
+                server_name: Some(sw.name.clone()),
+            });
+            continue;
+        }
+        if (server_changed || ws.synced_name.is_none()) && ws.name != sw.name {
+            ws.name = sw.name.clone();
+            ws.updated_at_unix = now;
+            report.renamed_locally.push(ws.id.clone());
+        }
+        if server_changed || ws.synced_name.is_none() {
+            ws.synced_name = Some(sw.name.clone());
+        }
+        if sw.workspace_type == WorkspaceType::Team {
+            ws.team_id = Some(sw.owner_id);
+        }
+    }
+    state.workspaces.retain(|ws| !removed.contains(&ws.id));
+    report.removed_locally = removed;
+
+    let linked: HashSet<i64> = state.workspaces.iter().filter_map(|ws| ws.server_id).collect();
+    let mut incoming: Vec<&ServerWorkspace> = server
+        .values()
+        .filter(|sw| !linked.contains(&sw.workspace_id))
+        .filter(|sw| !state.removed_server_ids.contains(&sw.workspace_id))
+        .collect();
+    incoming.sort_by_key(|sw| sw.workspace_id);
+    for sw in incoming {
+        let mut ws = server_workspace_to_local(sw, now, tz);
+        ws.sort_order = workspace::next_sort_order(state);
+        report.added_locally.push(ws.id.clone());
+        state.workspaces.push(ws);
+    }
+
+    // Tombstones are only needed while the server still has the workspace.
+    state.removed_server_ids.retain(|id| server.contains_key(id));
+    state.last_synced_at_unix = Some(now);
+    report
+}
+
+/// Create the tags and tasks of templates whose workspace now exists on the
+/// server. Failures are reported and retried on the next sync.
+async fn apply_pending_templates(app: &tauri::AppHandle, client: &ApiClient, report: &mut WorkspaceSyncReport) {
+    let state = workspace::load_state(app);
+    let templates = workspace_template::load_templates(app).templates;
+    let tz = workspace_template::timezone(app);
+    for ws in state.workspaces.iter().filter(|ws| !ws.is_trashed()) {
+        let Some(template_id) = ws.pending_template_id.as_deref() else {
+            continue;
+        };
+        let Some(target) = workspace_template::target_for(app, ws) else {
+            continue;
+        };
+        let result = match templates.iter().find(|t| t.id == template_id) {
+            Some(template) => workspace_template::instantiate(client, template, &target, tz).await,
+            // Deleted since; nothing left to apply.
+            None => Ok(()),
+        };
+        if let Err(e) = result {
+            report.failures.push(failure(&ws.id, &e));
+            continue;
+        }
+        let cleared = workspace::update_state(app, |state| {
+            if let Some(ws) = state.workspaces.iter_mut().find(|w| w.id == ws.id) {
+                ws.pending_template_id = None;
+            }
+            Ok(())
+        });
+        match cleared {
+            Ok(_) => report.templates_applied.push(ws.id.clone()),
+            Err(e) => log::warn!("workspace sync: failed to clear template of {}: {}", ws.id, e),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn sync_workspaces(app: tauri::AppHandle) -> AppResult<WorkspaceSyncReport> {
+    let client = ApiClient::from_app(&app);
+    let member_id = account::load_session(&app)
+        .member_id
+        .and_then(|id| id.trim().parse::<i64>().ok());
+    let snapshot = workspace::load_state(&app);
+    let exchange = exchange(&client, &snapshot, member_id).await?;
+    let tz = time::parse_timezone(&preferences::current_preferences(&app).timezone);
+
+    let mut report = None;
+    let mut before = WorkspaceIdMap::default();
+    let mut previous_current = None;
+    let state = workspace::update_state(&app, |state| {
+        before = WorkspaceIdMap::new(state);
+        previous_current = state.current_workspace_id.clone();
+        report = Some(merge(state, exchange, now_unix(), tz));
+        Ok(())
+    })?;
+    let mut report = report.unwrap_or_default();
+
+    let after = WorkspaceIdMap::new(&state);
+    for id in &report.added_locally {
+        if let Some(identity) = after.by_local_id(id) {
+            workspace::notify(&app, WorkspaceChange::Created(identity.clone()));
+        }
+    }
+    for id in &report.renamed_locally {
+        if let Some(identity) = after.by_local_id(id) {
+            workspace::notify(&app, WorkspaceChange::Renamed(identity.clone()));
+        }
+    }
+    for id in &report.removed_locally {
+        if let Some(identity) = before.by_local_id(id) {
+            workspace::notify(&app, WorkspaceChange::Deleted(identity.clone()));
+            if previous_current.as_deref() == Some(id.as_str()) {
+                workspace::notify_current_replaced(&app, identity, &state);
+            }
+        }
+    }
+    apply_pending_templates(&app, &client, &mut report).await;
+    log::info!(
+        "workspace sync: +{} server, +{} local, -{} local, {} conflicts, {} failures",
+        report.created_on_server.len(),
+        report.added_locally.len(),
+        report.removed_locally.len(),
+        report.conflicts.len(),
+        report.failures.len()
+    );
+    Ok(report)
+}
+
+/// Settle a conflict by keeping one side. Keeping the local name pushes it
+/// on the next sync; keeping the server side adopts its name, or drops the
+/// workspace if it was deleted on the server.
+#[tauri::command]
+pub fn resolve_workspace_conflict(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    keep_local: bool,
+    server_name: Option<String>,
+) -> AppResult<WorkspaceState> {
+    let mut change = None;
+    let mut was_current = false;
+    let state = workspace::update_state(&app, |state| {
+        was_current = state.current_workspace_id.as_deref() == Some(workspace_id.as_str());
+        let ws = state
+            .workspaces
+            .iter_mut()
+            .find(|ws| ws.id == workspace_id)
+            .ok_or_else(|| workspace::workspace_not_found(&workspace_id))?;
+        match (keep_local, server_name) {
+            // Re-created on the next sync.
+            (true, None) => {
+                ws.server_id = None;
+                ws.synced_name = None;
+            }
+            (true, Some(server_name)) => ws.synced_name = Some(server_name),
+            (false, Some(server_name)) => {
+                ws.name = server_name.clone();
+                ws.synced_name = Some(server_name);
+                ws.updated_at_unix = now_unix();
+                change = Some(WorkspaceChange::Renamed(ws.identity()));
+            }
+            (false, None) => {
+                change = Some(WorkspaceChange::Deleted(ws.identity()));
+                state.workspaces.retain(|ws| ws.id != workspace_id);
+            }
+        }
+        Ok(())
+    })?;
+
+    if let Some(change) = change {
+        workspace::notify(&app, change.clone());
+        if let WorkspaceChange::Deleted(identity) = &change {
+            if was_current {
+                workspace::notify_current_replaced(&app, identity, &state);
+            }
+        }
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// Minimal stand-in for the Pecal API: `/api/me/workspaces`, create and
+    /// rename, backed by an in-memory list. Records every request line.
+    struct StandIn {
+        base_url: String,
+        workspaces: Arc<Mutex<Vec<ServerWorkspace>>>,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StandIn {
+        fn start(initial: Vec<ServerWorkspace>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let base_url = format!("http://{}", listener.local_addr().unwrap());
+            let workspaces = Arc::new(Mutex::new(initial));
+            let requests = Arc::new(Mutex::new(Vec::new()));
+            let (ws, reqs) = (workspaces.clone(), requests.clone());
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let mut length = 0;
+                    loop {
+                        let mut header = String::new();
+                        reader.read_line(&mut header).unwrap();
+                        if header.trim().is_empty() {
+                            break;
+                        }
+                        if let Some(v) = header.to_lowercase().strip_prefix("content-length:") {
+                            length = v.trim().parse().unwrap();
+                        }
+                    }
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+                    let body: serde_json::Value =
+                        serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+
+                    let mut parts = line.split_whitespace();
+                    let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+                    reqs.lock().unwrap().push(format!("{} {}", method, path));
+                    let mut list = ws.lock().unwrap();
+                    let (status, reply) = match (method, path) {
+                        ("GET", "/api/me/workspaces") => {
+                            (200, serde_json::json!({ "workspaces": *list }))
+                        }
+                        ("POST", "/api/workspaces") => {
+                            let created = ServerWorkspace {
+                                workspace_id: list.iter().map(|w| w.workspace_id).max().unwrap_or(0) + 1,
+                                workspace_type: serde_json::from_value(body["type"].clone()).unwrap(),
+                                owner_id: body["owner_id"].as_i64().unwrap(),
+                                name: body["name"].as_str().unwrap().to_string(),
+                                created_at: None,
+                            };
+                            list.push(created.clone());
+                            (201, serde_json::json!({ "workspace": created }))
+                        }
+                        ("PATCH", p) => {
+                            let id: i64 = p.rsplit('/').next().unwrap().parse().unwrap();
+                            match list.iter_mut().find(|w| w.workspace_id == id) {
+                                Some(w) => {
+                                    w.name = body["name"].as_str().unwrap().to_string();
+                                    (200, serde_json::json!({ "success": true }))
+                                }
+                                None => (404, serde_json::json!({ "error": "Workspace not found" })),
+                            }
+                        }
+                        _ => (404, serde_json::json!({ "error": "not found" })),
+                    };
+                    let reply = reply.to_string();
+                    let mut stream = stream;
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        reply.len(),
+                        reply
+                    );
+                }
+            });
+            StandIn {
+                base_url,
+                workspaces,
+                requests,
+            }
+        }
+
+        fn client(&self) -> ApiClient {
+            ApiClient::new(self.base_url.clone(), Some("token".to_string()))
+        }
+
+        fn names(&self) -> Vec<String> {
+            self.workspaces.lock().unwrap().iter().map(|w| w.name.clone()).collect()
+        }
+
+        fn count(&self, prefix: &str) -> usize {
+            self.requests.lock().unwrap().iter().filter(|r| r.starts_with(prefix)).count()
+        }
+    }
+
+    fn server_ws(id: i64, workspace_type: WorkspaceType, name: &str) -> ServerWorkspace {
+        ServerWorkspace {
+            workspace_id: id,
+            workspace_type,
+            owner_id: 7,
+            name: name.to_string(),
+            created_at: None,
+        }
+    }
+
+    fn local_ws(id: &str, name: &str, server_id: Option<i64>, synced_name: Option<&str>) -> Workspace {
+        Workspace {
+            id: id.to_string(),
+            name: name.to_string(),
+            workspace_type: WorkspaceType::Personal,
+            plan_tier: None,
+            team_id: None,
+            created_at_unix: 0,
+            updated_at_unix: 0,
+            server_id,
+            synced_name: synced_name.map(str::to_string),
+            archived_at_unix: None,
+            trashed_at_unix: None,
+            sort_order: 0,
+            pinned: false,
+            appearance: WorkspaceAppearance::default(),
+            pending_template_id: None,
+        }
+    }
+
+    fn sync(server: &StandIn, state: &mut WorkspaceState) -> WorkspaceSyncReport {
+        let exchange = tauri::async_runtime::block_on(exchange(&server.client(), state, Some(7))).unwrap();
+        merge(state, exchange, 100, chrono_tz::Asia::Seoul)
+    }
+
+    #[test]
+    fn first_sync_adopts_server_workspaces() {
+        let server = StandIn::start(vec![
+            server_ws(1, WorkspaceType::Personal, "개인"),
+            ServerWorkspace {
+                created_at: Some("2025-01-02 09:00:00".to_string()),
+                ..server_ws(2, WorkspaceType::Team, "팀")
+            },
+        ]);
+        let mut state = WorkspaceState {
+            current_workspace_id: Some("ws_1".to_string()),
+            workspaces: vec![local_ws("ws_1", workspace::DEFAULT_WORKSPACE_NAME, None, None)],
+            ..WorkspaceState::default()
+        };
+
+        let report = sync(&server, &mut state);
+
+        assert_eq!(server.count("POST"), 0, "default workspace must not be duplicated");
+        assert_eq!(state.workspaces.len(), 2);
+        assert_eq!(state.workspaces[0].server_id, Some(1));
+        assert_eq!(state.workspaces[0].name, "개인");
+        assert_eq!(state.workspaces[1].team_id, Some(7));
+        assert_eq!(report.added_locally, vec![state.workspaces[1].id.clone()]);
+        assert_eq!(state.workspaces[1].server_id, Some(2));
+        // 09:00 KST
+        assert_eq!(state.workspaces[1].created_at_unix, 1_735_776_000);
+        assert_eq!(state.last_synced_at_unix, Some(100));
+    }
+
+    #[test]
+    fn pushes_local_creates_and_renames() {
+        let server = StandIn::start(vec![server_ws(1, WorkspaceType::Personal, "old")]);
+        let mut state = WorkspaceState {
+            workspaces: vec![
+                local_ws("ws_1", "new", Some(1), Some("old")),
+                local_ws("ws_2", "local only", None, None),
+            ],
+            ..WorkspaceState::default()
+        };
+
+        let report = sync(&server, &mut state);
+
+        assert_eq!(server.names(), vec!["new", "local only"]);
+        assert_eq!(report.renamed_on_server, vec!["ws_1".to_string()]);
+        assert_eq!(report.created_on_server, vec!["ws_2".to_string()]);
+        assert!(state.workspaces.iter().all(|ws| ws.server_id.is_some()
+            && ws.synced_name.as_deref() == Some(ws.name.as_str())));
+
+        // Nothing left to push.
+        sync(&server, &mut state);
+        assert_eq!(server.count("POST"), 1);
+        assert_eq!(server.count("PATCH"), 1);
+    }
+
+    #[test]
+    fn server_deletions_remove_clean_entries_only() {
+        let server = StandIn::start(vec![server_ws(3, WorkspaceType::Personal, "kept")]);
+        let mut state = WorkspaceState {
+            workspaces: vec![
+                local_ws("ws_1", "clean", Some(1), Some("clean")),
+                local_ws("ws_2", "renamed", Some(2), Some("before")),
+                local_ws("ws_3", "kept", Some(3), Some("kept")),
+            ],
+            ..WorkspaceState::default()
+        };
+
+        let report = sync(&server, &mut state);
+
+        assert_eq!(report.removed_locally, vec!["ws_1".to_string()]);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].kind, SyncConflictKind::DeletedOnServer);
+        let ids: Vec<&str> = state.workspaces.iter().map(|ws| ws.id.as_str()).collect();
+        assert_eq!(ids, vec!["ws_2", "ws_3"]);
+    }
+
+    #[test]
+    fn rename_on_both_sides_is_a_conflict() {
+        let server = StandIn::start(vec![
+            server_ws(1, WorkspaceType::Personal, "server name"),
+            server_ws(2, WorkspaceType::Personal, "pulled"),
+        ]);
+        let mut state = WorkspaceState {
+            workspaces: vec![
+                local_ws("ws_1", "local name", Some(1), Some("base")),
+                local_ws("ws_2", "base", Some(2), Some("base")),
+            ],
+            ..WorkspaceState::default()
+        };
+
+        let report = sync(&server, &mut state);
+
+        assert_eq!(server.count("PATCH"), 0);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].kind, SyncConflictKind::RenamedOnBoth);
+        assert_eq!(report.conflicts[0].server_name.as_deref(), Some("server name"));
+        assert_eq!(state.workspaces[0].name, "local name");
+        assert_eq!(report.renamed_locally, vec!["ws_2".to_string()]);
+        assert_eq!(state.workspaces[1].name, "pulled");
+    }
+
+    #[test]
+    fn locally_deleted_workspaces_are_not_pulled_again() {
+        let server = StandIn::start(vec![
+            server_ws(1, WorkspaceType::Personal, "a"),
+            server_ws(2, WorkspaceType::Personal, "b"),
+        ]);
+        let mut state = WorkspaceState {
+            workspaces: vec![local_ws("ws_1", "a", Some(1), Some("a"))],
+            removed_server_ids: vec![2, 9],
+            ..WorkspaceState::default()
+        };
+
+        let report = sync(&server, &mut state);
+
+        assert!(report.added_locally.is_empty());
+        assert_eq!(state.workspaces.len(), 1);
+        assert_eq!(state.removed_server_ids, vec![2]);
+    }
+}
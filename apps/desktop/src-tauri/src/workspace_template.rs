@@ -0,0 +1,463 @@
+/// Workspace templates: the structure of an existing workspace (tags, task
+/// color presets, recurring task skeletons with their reminders, appearance)
+/// saved to `workspace_templates.json` and replayed into new workspaces.
+///
+/// Appearance is applied locally when the workspace is created. Tags and
+/// tasks live on the server, so the rest is applied by the next workspace
+/// sync once the workspace exists there. Applying is idempotent (tags match
+/// by name, tasks by title) so a failed attempt is simply retried.
+///
+/// Alarm policies are carried as each skeleton's `reminder_minutes`. The
+/// device-wide `AlarmPolicies` are not workspace data and are not captured.
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::account;
+use crate::api::{ApiClient, ApiError};
+use crate::config_store;
+use crate::error::{AppError, AppResult};
+use crate::migrations;
+use crate::preferences;
+use crate::time::{self, now_unix};
+use crate::workspace::{self, WorkspaceAppearance, WorkspaceType};
+
+const TEMPLATES_FILE: &str = migrations::WORKSPACE_TEMPLATES.file;
+const MAX_TEMPLATE_NAME_LEN: usize = 50;
+/// Largest page `GET /api/tasks` serves.
+const TASKS_PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TemplateTag {
+    pub name: String,
+    pub color: String,
+}
+
+/// A recurring task without its dates: it repeats on `weekdays` for
+/// `span_days` starting the day after it is instantiated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringTaskSkeleton {
+    pub title: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Names from `WorkspaceTemplate.tags`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Local start time, minutes since midnight.
+    pub start_minute: u32,
+    pub duration_minutes: i64,
+    /// 0 = Sunday.
+    pub weekdays: Vec<u8>,
+    pub span_days: i64,
+    #[serde(default)]
+    pub reminder_minutes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceTemplate {
+    pub id: String,
+    pub name: String,
+    pub workspace_type: WorkspaceType,
+    #[serde(default)]
+    pub appearance: WorkspaceAppearance,
+    #[serde(default)]
+    pub tags: Vec<TemplateTag>,
+    #[serde(default)]
+    pub color_presets: Vec<String>,
+    #[serde(default)]
+    pub recurring_tasks: Vec<RecurringTaskSkeleton>,
+    pub created_at_unix: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceTemplates {
+    #[serde(default)]
+    pub templates: Vec<WorkspaceTemplate>,
+}
+
+/// Server side of a workspace a template is captured from or applied to.
+#[derive(Debug, Clone)]
+pub struct TemplateTarget {
+    pub workspace_id: i64,
+    pub owner_type: WorkspaceType,
+    /// member_id for personal workspaces, team_id for team workspaces.
+    pub owner_id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerTag {
+    tag_id: i64,
+    name: String,
+    color: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    tags: Vec<ServerTag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateTagResponse {
+    #[serde(default)]
+    tag_id: Option<i64>,
+    #[serde(default)]
+    tag: Option<ServerTag>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ColorPresetsResponse {
+    #[serde(default)]
+    presets: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerRecurrence {
+    start_date: String,
+    end_date: String,
+    #[serde(default)]
+    weekdays: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagRef {
+    tag_id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerTask {
+    title: String,
+    start_time: String,
+    end_time: String,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    reminder_minutes: Option<i64>,
+    #[serde(default)]
+    recurrence: Option<ServerRecurrence>,
+    #[serde(default)]
+    tag_ids: Option<Vec<i64>>,
+    #[serde(default)]
+    tags: Option<Vec<TagRef>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TasksResponse {
+    #[serde(default)]
+    tasks: Vec<ServerTask>,
+    #[serde(default, rename = "totalPages")]
+    total_pages: u32,
+}
+
+fn owner_type_param(owner_type: &WorkspaceType) -> &'static str {
+    match owner_type {
+        WorkspaceType::Personal => "personal",
+        WorkspaceType::Team => "team",
+    }
+}
+
+fn skeleton_from_task(task: &ServerTask, tag_names: &HashMap<i64, String>, tz: Tz) -> Option<RecurringTaskSkeleton> {
+    let recurrence = task.recurrence.as_ref()?;
+    let start = time::parse_server_datetime(&task.start_time, tz)?;
+    let end = time::parse_server_datetime(&task.end_time, tz)?;
+    let first = chrono::NaiveDate::parse_from_str(&recurrence.start_date, "%Y-%m-%d").ok()?;
+    let last = chrono::NaiveDate::parse_from_str(&recurrence.end_date, "%Y-%m-%d").ok()?;
+    let tag_ids: Vec<i64> = match (&task.tag_ids, &task.tags) {
+        (Some(ids), _) => ids.clone(),
+        (None, Some(tags)) => tags.iter().map(|t| t.tag_id).collect(),
+        (None, None) => Vec::new(),
+    };
+    Some(RecurringTaskSkeleton {
+        title: task.title.clone(),
+        content: task.content.clone().filter(|c| !c.is_empty()),
+        color: task.color.clone(),
+        tags: tag_ids.iter().filter_map(|id| tag_names.get(id).cloned()).collect(),
+        start_minute: time::local_minute_of_day(start, tz),
+        duration_minutes: ((end - start) / 60).max(1),
+        weekdays: recurrence.weekdays.clone(),
+        span_days: (last - first).num_days().max(0),
+        reminder_minutes: task.reminder_minutes,
+    })
+}
+
+async fn fetch_tags(client: &ApiClient, target: &TemplateTarget) -> Result<Vec<ServerTag>, ApiError> {
+    let path = format!(
+        "/api/tags?owner_type={}&owner_id={}",
+        owner_type_param(&target.owner_type),
+        target.owner_id
+    );
+    Ok(client.get::<TagsResponse>(&path).await?.tags)
+}
+
+/// Every task of the workspace, page by page.
+async fn fetch_tasks(client: &ApiClient, target: &TemplateTarget) -> Result<Vec<ServerTask>, ApiError> {
+    let mut tasks = Vec::new();
+    let mut page = 1;
+    loop {
+        let path = format!(
+            "/api/tasks?workspace_id={}&page={}&limit={}",
+            target.workspace_id, page, TASKS_PAGE_SIZE
+        );
+        let response: TasksResponse = client.get(&path).await?;
+        let done = response.tasks.is_empty() || page >= response.total_pages;
+        tasks.extend(response.tasks);
+        if done {
+            return Ok(tasks);
+        }
+        page += 1;
+    }
+}
+
+/// Read the structure of a server workspace.
+pub async fn capture(
+    client: &ApiClient,
+    target: &TemplateTarget,
+    tz: Tz,
+) -> Result<(Vec<TemplateTag>, Vec<String>, Vec<RecurringTaskSkeleton>), ApiError> {
+    let server_tags = fetch_tags(client, target).await?;
+    let presets: ColorPresetsResponse = client.get("/api/me/task-color-presets").await?;
+    let tasks = fetch_tasks(client, target).await?;
+
+    let tag_names: HashMap<i64, String> = server_tags.iter().map(|t| (t.tag_id, t.name.clone())).collect();
+    let mut seen = HashSet::new();
+    let skeletons = tasks
+        .iter()
+        .filter_map(|task| skeleton_from_task(task, &tag_names, tz))
+        // Recurring tasks come back once per occurrence.
+        .filter(|s| seen.insert((s.title.clone(), s.start_minute, s.weekdays.clone())))
+        .collect();
+    let tags = server_tags
+        .into_iter()
+        .map(|t| TemplateTag {
+            name: t.name,
+            color: t.color,
+        })
+        .collect();
+    Ok((tags, presets.presets, skeletons))
+}
+
+/// Create whatever the target does not have yet.
+pub async fn instantiate(
+    client: &ApiClient,
+    template: &WorkspaceTemplate,
+    target: &TemplateTarget,
+    tz: Tz,
+) -> Result<(), ApiError> {
+    let mut tag_ids: HashMap<String, i64> = fetch_tags(client, target)
+        .await?
+        .into_iter()
+        .map(|t| (t.name, t.tag_id))
+        .collect();
+    for tag in &template.tags {
+        if tag_ids.contains_key(&tag.name) {
+            continue;
+        }
+        let body = serde_json::json!({
+            "name": tag.name,
+            "color": tag.color,
+            "owner_type": owner_type_param(&target.owner_type),
+            "owner_id": target.owner_id,
+        });
+        let created: CreateTagResponse = client.post("/api/tags", &body).await?;
+        if let Some(id) = created.tag.map(|t| t.tag_id).or(created.tag_id) {
+            tag_ids.insert(tag.name.clone(), id);
+        }
+    }
+
+    if !template.color_presets.is_empty() {
+        let current: ColorPresetsResponse = client.get("/api/me/task-color-presets").await?;
+        let mut presets = current.presets.clone();
+        for color in &template.color_presets {
+            if !presets.iter().any(|p| p.eq_ignore_ascii_case(color)) {
+                presets.push(color.clone());
+            }
+        }
+        if presets.len() != current.presets.len() {
+            client
+                .patch::<_, ColorPresetsResponse>("/api/me/task-color-presets", &serde_json::json!({ "presets": presets }))
+                .await?;
+        }
+    }
+
+    if template.recurring_tasks.is_empty() {
+        return Ok(());
+    }
+    let existing: HashSet<String> = fetch_tasks(client, target)
+        .await?
+        .into_iter()
+        .filter(|t| t.recurrence.is_some())
+        .map(|t| t.title)
+        .collect();
+    let first_day = time::local_date(now_unix(), tz).succ_opt().unwrap_or_default();
+    for skeleton in template.recurring_tasks.iter().filter(|s| !existing.contains(&s.title)) {
+        let start = time::local_time_to_unix(first_day, skeleton.start_minute, tz);
+        let end = start + skeleton.duration_minutes * 60;
+        let last_day = first_day + chrono::Duration::days(skeleton.span_days);
+        let body = serde_json::json!({
+            "title": skeleton.title,
+            "content": skeleton.content,
+            "color": skeleton.color,
+            "tag_ids": skeleton.tags.iter().filter_map(|name| tag_ids.get(name)).collect::<Vec<_>>(),
+            "start_time": time::local_datetime(start, tz).to_rfc3339(),
+            "end_time": time::local_datetime(end, tz).to_rfc3339(),
+            "reminder_minutes": skeleton.reminder_minutes,
+            "recurrence": {
+                "enabled": true,
+                "start_date": first_day.format("%Y-%m-%d").to_string(),
+                "end_date": last_day.format("%Y-%m-%d").to_string(),
+                "weekdays": skeleton.weekdays,
+            },
+            "workspace_id": target.workspace_id,
+        });
+        client.post::<_, serde_json::Value>("/api/tasks", &body).await?;
+    }
+    Ok(())
+}
+
+fn templates_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    config_store::config_path(app, TEMPLATES_FILE)
+}
+
+pub fn load_templates(app: &tauri::AppHandle) -> WorkspaceTemplates {
+    templates_path(app)
+        .ok()
+        .and_then(|path| config_store::read(&path, &migrations::WORKSPACE_TEMPLATES))
+        .unwrap_or_default()
+}
+
+fn update_templates(app: &tauri::AppHandle, f: impl FnOnce(&mut WorkspaceTemplates) -> AppResult<()>) -> AppResult<()> {
+    let path = templates_path(app).map_err(AppError::storage)?;
+    config_store::with_lock(&path, || {
+        let mut templates = load_templates(app);
+        f(&mut templates)?;
+        // Resolved again in case the data directory moved while waiting.
+        let path = templates_path(app).map_err(AppError::storage)?;
+        config_store::write(&path, &migrations::WORKSPACE_TEMPLATES, &templates).map_err(AppError::storage)
+    })?
+}
+
+/// Replace every template. Used by backup restore.
+pub fn replace_templates(app: &tauri::AppHandle, incoming: WorkspaceTemplates) -> AppResult<()> {
+    update_templates(app, |templates| {
+        *templates = incoming;
+        Ok(())
+    })
+}
+
+/// Remove `workspace_templates.json`. Used on logout.
+pub fn clear_templates(app: &tauri::AppHandle) -> Result<(), String> {
+    config_store::remove(&templates_path(app)?)
+}
+
+pub fn template_not_found(template_id: &str) -> AppError {
+    AppError::not_found("template_not_found", "Workspace template not found")
+        .with_details(serde_json::json!({ "template_id": template_id }))
+}
+
+/// The template to create a workspace of `workspace_type` from.
+pub fn find_template(
+    app: &tauri::AppHandle,
+    template_id: &str,
+    workspace_type: &WorkspaceType,
+) -> AppResult<WorkspaceTemplate> {
+    let template = load_templates(app)
+        .templates
+        .into_iter()
+        .find(|t| t.id == template_id.trim())
+        .ok_or_else(|| template_not_found(template_id))?;
+    if &template.workspace_type != workspace_type {
+        return Err(AppError::validation(
+            "template_type_mismatch",
+            "Template was captured from a different workspace type",
+        )
+        .with_details(serde_json::json!({ "template_id": template.id })));
+    }
+    Ok(template)
+}
+
+pub fn timezone(app: &tauri::AppHandle) -> Tz {
+    time::parse_timezone(&preferences::current_preferences(app).timezone)
+}
+
+/// Server side of a local workspace; `None` until it is synced and its owner
+/// is known.
+pub fn target_for(app: &tauri::AppHandle, ws: &workspace::Workspace) -> Option<TemplateTarget> {
+    let owner_id = match ws.workspace_type {
+        WorkspaceType::Personal => account::load_session(app)
+            .member_id
+            .and_then(|id| id.trim().parse::<i64>().ok()),
+        WorkspaceType::Team => ws.team_id,
+    }?;
+    Some(TemplateTarget {
+        workspace_id: ws.server_id?,
+        owner_type: ws.workspace_type.clone(),
+        owner_id,
+    })
+}
+
+#[tauri::command]
+pub fn list_workspace_templates(app: tauri::AppHandle) -> Vec<WorkspaceTemplate> {
+    load_templates(&app).templates
+}
+
+#[tauri::command]
+pub async fn capture_workspace_template(
+    app: tauri::AppHandle,
+    workspace_id: String,
+    name: String,
+) -> AppResult<WorkspaceTemplate> {
+    let name = name.trim().to_string();
+    let len = name.chars().count();
+    if len == 0 || len > MAX_TEMPLATE_NAME_LEN {
+        return Err(AppError::validation(
+            "template_name_invalid",
+            format!("Template name must be 1 to {} characters", MAX_TEMPLATE_NAME_LEN),
+        )
+        .with_details(serde_json::json!({ "max": MAX_TEMPLATE_NAME_LEN })));
+    }
+    let ws = workspace::load_state(&app)
+        .workspaces
+        .into_iter()
+        .find(|ws| ws.id == workspace_id.trim())
+        .ok_or_else(|| workspace::workspace_not_found(&workspace_id))?;
+    let target = target_for(&app, &ws).ok_or_else(|| {
+        AppError::conflict("workspace_not_synced", "Workspace has not been synced with the server yet")
+            .with_details(serde_json::json!({ "workspace_id": ws.id }))
+    })?;
+
+    let client = ApiClient::from_app(&app);
+    let (tags, color_presets, recurring_tasks) = capture(&client, &target, timezone(&app)).await?;
+    let template = WorkspaceTemplate {
+        id: format!("tpl_{}", uuid::Uuid::new_v4()),
+        name,
+        workspace_type: ws.workspace_type,
+        appearance: ws.appearance,
+        tags,
+        color_presets,
+        recurring_tasks,
+        created_at_unix: now_unix(),
+    };
+    let saved = template.clone();
+    update_templates(&app, |templates| {
+        templates.templates.push(saved);
+        Ok(())
+    })?;
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn delete_workspace_template(app: tauri::AppHandle, template_id: String) -> AppResult<()> {
+    update_templates(&app, |templates| {
+        let before = templates.templates.len();
+        templates.templates.retain(|t| t.id != template_id.trim());
+        if templates.templates.len() == before {
+            return Err(template_not_found(&template_id));
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,39 @@
+import { type NextRequest, NextResponse } from "next/server";
+import { getAuthUser } from "@/lib/auth-helper";
+import {
+  checkTeamMembership,
+  getPermissionsByMember,
+  getTeamById,
+} from "@/lib/team";
+
+// 로그인한 멤버 본인의 권한 코드 (팀 소유자가 아니어도 조회 가능)
+export async function GET(
+  request: NextRequest,
+  { params }: { params: Promise<{ id: string }> },
+) {
+  const user = await getAuthUser(request);
+  if (!user) {
+    return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+  }
+
+  const { id } = await params;
+  const teamId = Number(id);
+  if (Number.isNaN(teamId)) {
+    return NextResponse.json({ error: "Invalid team id" }, { status: 400 });
+  }
+
+  const team = await getTeamById(teamId);
+  if (!team) {
+    return NextResponse.json({ error: "Team not found" }, { status: 404 });
+  }
+
+  if (
+    team.created_by !== user.memberId &&
+    !(await checkTeamMembership(teamId, user.memberId))
+  ) {
+    return NextResponse.json({ error: "Forbidden" }, { status: 403 });
+  }
+
+  const permissions = await getPermissionsByMember(teamId, user.memberId);
+  return NextResponse.json({ permissions });
+}
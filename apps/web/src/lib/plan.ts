@@ -7,6 +7,7 @@ export interface Plan {
   price: number;
   max_members: number;
   max_storage_mb: number;
+  max_file_size_mb: number;
   plan_type: "personal" | "team";
   created_at: Date;
 }
@@ -19,6 +20,7 @@ export async function getAllPlans(): Promise<Plan[]> {
       price,
       max_members,
       max_storage_mb,
+      max_file_size_mb,
       plan_type,
       created_at
     FROM plans
@@ -35,6 +37,7 @@ export async function getPlanById(planId: number): Promise<Plan | null> {
       price,
       max_members,
       max_storage_mb,
+      max_file_size_mb,
       plan_type,
       created_at
     FROM plans